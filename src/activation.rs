@@ -1,6 +1,6 @@
 //! Provides utility functionality when working with common activation (or transfer) functions.
 
-use ndarray::NdFloat;
+use ndarray::{NdFloat, ArrayViewMut1};
 
 /// Represents an activation function.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -44,6 +44,48 @@ pub enum Activation {
 	Gaussian,
 }
 
+/// Represents the activation function of an output layer.
+///
+/// Output layers may use any element-wise activation function
+/// or normalize all of their outputs with softmax.
+///
+/// Element-wise activation functions convert into this,
+/// so they can be passed directly to `TopologyBuilder::output`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OutputActivation {
+	/// The given element-wise activation function.
+	Element(Activation),
+
+	/// Soft Max: *ƒ(xᵢ) = eˣⁱ ⋅ (Σⱼ eˣʲ)⁻¹*
+	///
+	/// Normalizes all outputs of the layer into a probability distribution,
+	/// e.g. for multi-class classification.
+	///
+	/// Output layers with softmax are trained with `Loss::CategoricalCrossEntropy`
+	/// whose fused gradient with respect to the inputs of the softmax is simply
+	/// *target - output*.
+	SoftMax,
+}
+
+impl OutputActivation {
+	/// Returns the element-wise activation function of the output layer.
+	///
+	/// This is `Identity` for softmax which is applied to the
+	/// whole layer after the element-wise activation function.
+	pub fn element_wise(self) -> Activation {
+		match self {
+			OutputActivation::Element(activation) => activation,
+			OutputActivation::SoftMax             => Activation::Identity
+		}
+	}
+}
+
+impl From<Activation> for OutputActivation {
+	fn from(activation: Activation) -> Self {
+		OutputActivation::Element(activation)
+	}
+}
+
 impl Activation {
 	/// Returns `f(x)` with a given `x` and `f` as the base function.
 	pub fn base<F: NdFloat>(self, x: F) -> F {
@@ -84,6 +126,16 @@ impl Activation {
 	}
 }
 
+/// Normalizes the given values into a probability distribution in place.
+///
+/// The maximum value is subtracted before exponentiation to avoid overflows.
+pub(crate) fn softmax(mut values: ArrayViewMut1<f32>) {
+	let max = values.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
+	values.mapv_inplace(|x| (x - max).exp());
+	let sum = values.scalar_sum();
+	values.mapv_inplace(|x| x / sum);
+}
+
 mod details {
 	use ndarray::NdFloat;
 
@@ -372,4 +424,16 @@ mod tests {
 		relative_eq!(act.derived(0.5), -0.7788008);
 		relative_eq!(act.derived(1.0), -0.7357589);
 	}
+
+	#[test]
+	fn softmax() {
+		use ndarray::prelude::*;
+		let mut values = arr1(&[1.0, 2.0, 3.0]);
+		super::softmax(values.view_mut());
+		assert_relative_eq!(values.scalar_sum(), 1.0, epsilon = 1e-6);
+		assert!(values[0] < values[1] && values[1] < values[2]);
+		let mut large = arr1(&[1001.0, 1002.0, 1003.0]);
+		super::softmax(large.view_mut());
+		assert!(large.iter().zip(values.iter()).all(|(l, v)| (l - v).abs() < 1e-6));
+	}
 }
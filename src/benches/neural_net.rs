@@ -36,7 +36,7 @@ fn update_gradients(bencher: &mut Bencher) {
 	use traits::UpdateGradients;
 	let mut net = create_giant_net();
 	bencher.iter(|| {
-		net.update_gradients(&[1.0]);
+		net.update_gradients(&[1.0], Loss::MeanSquaredError);
 	});
 }
 
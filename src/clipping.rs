@@ -0,0 +1,117 @@
+//! Provides gradient clipping that bounds the weight gradients of a neural network
+//! before they are applied to prevent its weights from blowing up while training.
+
+use ndarray::prelude::*;
+
+use errors::ErrorKind::InvalidGradientClipping;
+use errors::Result;
+
+/// Represents the clipping of the weight gradients of all layers during training.
+///
+/// Weight gradients are clipped after the gradients have been updated
+/// and before the optimizer updates the weights with them.
+/// This includes the gradients of regularization penalties.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum GradientClipping {
+	/// Do not clip any gradients.
+	///
+	/// This is the default.
+	#[default]
+	None,
+
+	/// Clip every weight gradient to the range *[-c, c]* with the given threshold *c*.
+	Value(f32),
+
+	/// Scale all weight gradients down so that their global norm, i.e. the
+	/// L2 norm over the weight gradients of all layers, is at most the given threshold.
+	///
+	/// Unlike `Value` this keeps the direction of the gradients.
+	Norm(f32),
+}
+
+impl GradientClipping {
+	/// Checks if the threshold of this gradient clipping is positive and finite.
+	pub(crate) fn check_validity(&self) -> Result<()> {
+		match *self {
+			GradientClipping::Value(threshold) |
+			GradientClipping::Norm(threshold) if !(threshold > 0.0 && threshold.is_finite()) => {
+				Err(InvalidGradientClipping)
+			}
+			_ => Ok(())
+		}
+	}
+
+	/// Clips the given weight gradients of all layers.
+	pub(crate) fn clip(&self, gradients: &mut [ArrayViewMut2<f32>]) {
+		match *self {
+			GradientClipping::None => (),
+			GradientClipping::Value(threshold) => {
+				// Non-finite gradients are left alone so that diverged
+				// training is still detected instead of carried on.
+				for gradients in gradients.iter_mut() {
+					gradients.mapv_inplace(|gradient| {
+						if gradient.is_finite() { gradient.max(-threshold).min(threshold) } else { gradient }
+					});
+				}
+			}
+			GradientClipping::Norm(threshold) => {
+				let norm = gradients
+					.iter()
+					.flat_map(|gradients| gradients.iter())
+					.map(|&gradient| f64::from(gradient) * f64::from(gradient))
+					.sum::<f64>()
+					.sqrt();
+				if norm > f64::from(threshold) {
+					let factor = (f64::from(threshold) / norm) as f32;
+					for gradients in gradients.iter_mut() {
+						gradients.mapv_inplace(|gradient| gradient * factor);
+					}
+				}
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clip_value() {
+		let mut fst = arr2(&[[1.0, -2.0], [0.5, 3.0]]);
+		let mut snd = arr2(&[[-0.25, -1.5]]);
+		GradientClipping::Value(1.0).clip(&mut [fst.view_mut(), snd.view_mut()]);
+		assert_eq!(fst, arr2(&[[1.0, -1.0], [0.5, 1.0]]));
+		assert_eq!(snd, arr2(&[[-0.25, -1.0]]));
+	}
+
+	#[test]
+	fn clip_value_non_finite() {
+		let mut gradients = arr2(&[[f32::NAN, f32::INFINITY, -2.0]]);
+		GradientClipping::Value(1.0).clip(&mut [gradients.view_mut()]);
+		assert!(gradients[(0, 0)].is_nan());
+		assert_eq!(gradients[(0, 1)], f32::INFINITY);
+		assert_eq!(gradients[(0, 2)], -1.0);
+	}
+
+	#[test]
+	fn clip_norm() {
+		let mut fst = arr2(&[[3.0, 0.0]]);
+		let mut snd = arr2(&[[0.0], [-4.0]]);
+		GradientClipping::Norm(10.0).clip(&mut [fst.view_mut(), snd.view_mut()]);
+		assert_eq!(fst, arr2(&[[3.0, 0.0]]));
+		GradientClipping::Norm(2.5).clip(&mut [fst.view_mut(), snd.view_mut()]);
+		assert_eq!(fst, arr2(&[[1.5, 0.0]]));
+		assert_eq!(snd, arr2(&[[0.0], [-2.0]]));
+	}
+
+	#[test]
+	fn validity() {
+		assert_eq!(GradientClipping::None.check_validity(), Ok(()));
+		assert_eq!(GradientClipping::Value(0.5).check_validity(), Ok(()));
+		assert_eq!(GradientClipping::Norm(0.0).check_validity(), Err(InvalidGradientClipping));
+		assert_eq!(GradientClipping::Value(f32::NAN).check_validity(), Err(InvalidGradientClipping));
+		assert_eq!(GradientClipping::Norm(f32::INFINITY).check_validity(), Err(InvalidGradientClipping));
+	}
+}
@@ -9,6 +9,64 @@ pub enum ErrorKind {
 	/// Occures when invalid sample target sizes are recognized.
 	InvalidSampleTargetSize,
 
+	/// Occures when a sample weight is negative or not finite or
+	/// when all sample weights are zero for weighted scheduling.
+	InvalidSampleWeight,
+
+	/// Occures when a sample source provides no samples.
+	EmptySampleSource,
+
+	/// Occures when reading samples from a file fails.
+	SampleSourceIo,
+
+	/// Occures when a line of a sample file cannot be parsed into a sample.
+	InvalidSampleLine{
+		/// The line number of the invalid line, starting at `1`.
+		line: u64
+	},
+
+	/// Occures when a scheduling strategy other than `Scheduling::Iterative`
+	/// is used with a sample source.
+	InvalidScheduling,
+
+	/// Occures when the selected input or target columns of a CSV file
+	/// are empty, do not exist or overlap.
+	InvalidCsvColumns,
+
+	/// Occures when a row of a CSV file has a different number of values
+	/// than its header line or first row.
+	InvalidCsvRowLength{
+		/// The line number of the row, starting at `1`.
+		line: u64
+	},
+
+	/// Occures when a value of a CSV file is not a number or has unbalanced quotes.
+	InvalidCsvValue{
+		/// The line number of the value, starting at `1`.
+		line: u64,
+		/// The zero-based column of the value.
+		column: usize
+	},
+
+	/// Occures when IDX data is malformed or truncated or when
+	/// IDX images and labels do not match.
+	InvalidIdx,
+
+	/// Occures when an IDX label is out of bounds for the one-hot targets.
+	InvalidIdxLabel{
+		/// The zero-based index of the labeled item.
+		index: usize
+	},
+
+	/// Occures when a value of a CSV file is missing
+	/// and missing values are not allowed.
+	MissingCsvValue{
+		/// The line number of the value, starting at `1`.
+		line: u64,
+		/// The zero-based column of the value.
+		column: usize
+	},
+
 	/// Occures when the learning rate is not within the valid
 	/// range of `(0,1)`.
 	InvalidLearnRate,
@@ -17,13 +75,94 @@ pub enum ErrorKind {
 	/// valid range of `(0,1)`.
 	InvalidLearnMomentum,
 
-	/// Occures when the specified average net error
+	/// Occures when the specified recent loss
 	/// criterion is invalid.
-	InvalidRecentMSE,
+	InvalidRecentLoss,
 
-	/// Occures when the specified mean squared error
+	/// Occures when the specified latest loss
 	/// criterion is invalid.
-	InvalidLatestMSE,
+	InvalidLatestLoss,
+
+	/// Occures when the parameters of the specified
+	/// optimizer are invalid.
+	InvalidOptimizer,
+
+	/// Occures when the specified batch size is zero
+	/// or larger than the number of training samples.
+	InvalidBatchSize,
+
+	/// Occures when a per-column scaler does not have exactly one
+	/// non-nested scaler for every column or when targets are standardized
+	/// for an output activation function that cannot reach them.
+	InvalidScaler,
+
+	/// Occures when the specified loss function is invalid
+	/// or does not match the activation function of the output layer.
+	InvalidLoss,
+
+	/// Occures when a regularization term is negative or not finite,
+	/// when the weight decay times the learn rate is not below one
+	/// or when there is not exactly one regularization for every layer.
+	InvalidRegularization,
+
+	/// Occures when the threshold of the specified gradient clipping
+	/// is not positive and finite.
+	InvalidGradientClipping,
+
+	/// Occures when the patience of the early stopping
+	/// criterion is zero.
+	InvalidPatience,
+
+	/// Occures when the specified history interval is zero.
+	InvalidHistoryInterval,
+
+	/// Occures when the specified checkpoint interval is zero.
+	InvalidCheckpointInterval,
+
+	/// Occures when the specified rollback interval is zero.
+	InvalidRollbackInterval,
+
+	/// Occures when reading or writing a checkpoint file fails.
+	CheckpointIo,
+
+	/// Occures when a checkpoint file is malformed.
+	InvalidCheckpoint,
+
+	/// Occures when a composite criterion has no sub-criteria.
+	EmptyCriterion,
+
+	/// Occures when the fractions of a sample split are not
+	/// within `[0,1)` or leave no training samples.
+	InvalidSplit,
+
+	/// Occures when cross validation is requested with less than
+	/// two folds or more folds than training samples.
+	InvalidFolds,
+
+	/// Occures when early stopping is requested without
+	/// providing any validation samples.
+	MissingValidationSamples,
+
+	/// Occures when the outputs, gradients or weights of a neural network
+	/// are no longer finite while training, i.e. the training diverged,
+	/// and no rollback is possible.
+	TrainingDiverged{
+		/// The number of training iterations until the divergence.
+		iteration: u64,
+		/// The zero-based index of the first diverged layer, excluding the input layer.
+		layer: usize
+	},
+}
+
+#[allow(non_upper_case_globals)]
+impl ErrorKind {
+	/// Occures when the specified recent loss criterion is invalid.
+	#[deprecated(note = "renamed to `ErrorKind::InvalidRecentLoss`")]
+	pub const InvalidRecentMSE: ErrorKind = ErrorKind::InvalidRecentLoss;
+
+	/// Occures when the specified latest loss criterion is invalid.
+	#[deprecated(note = "renamed to `ErrorKind::InvalidLatestLoss`")]
+	pub const InvalidLatestMSE: ErrorKind = ErrorKind::InvalidLatestLoss;
 }
 
 /// Result type for procedures of this crate.
@@ -0,0 +1,174 @@
+//! Provides strategies to initialize the weights of neural layers.
+
+use std::fmt;
+use std::sync::Arc;
+
+use rand::Rng;
+use rand::distributions::{self, IndependentSample, Range};
+
+use activation::Activation;
+
+/// Represents a strategy to initialize the weights of a layer.
+///
+/// Most strategies scale the drawn weights by the number of input
+/// neurons (*fan-in*) and output neurons (*fan-out*) of the layer
+/// so that wide layers do not saturate their activation functions.
+#[derive(Clone)]
+pub enum Initializer {
+	/// All weights are zero.
+	///
+	/// This is the default for bias weights.
+	Zeros,
+
+	/// Weights are drawn uniformly from the given interval `[low, high)`.
+	Uniform(f32, f32),
+
+	/// Weights are drawn from a normal distribution with the given mean and standard deviation.
+	Normal(f32, f32),
+
+	/// Xavier (or Glorot) uniform: *U(-l, l)* with *l = sqrt(6 / (fan-in + fan-out))*
+	///
+	/// Suited for `Tanh`, `Logistic` and similar symmetric activation functions.
+	XavierUniform,
+
+	/// Xavier (or Glorot) normal: *N(0, s)* with *s = sqrt(2 / (fan-in + fan-out))*
+	XavierNormal,
+
+	/// He uniform: *U(-l, l)* with *l = sqrt(6 / fan-in)*
+	///
+	/// Suited for `ReLU` and similar rectifying activation functions.
+	HeUniform,
+
+	/// He normal: *N(0, s)* with *s = sqrt(2 / fan-in)*
+	HeNormal,
+
+	/// LeCun uniform: *U(-l, l)* with *l = sqrt(3 / fan-in)*
+	LeCunUniform,
+
+	/// LeCun normal: *N(0, s)* with *s = sqrt(1 / fan-in)*
+	LeCunNormal,
+
+	/// Weights are computed by the given function of fan-in and fan-out.
+	///
+	/// Use `Initializer::custom` to construct this.
+	Custom(Arc<dyn Fn(usize, usize) -> f32 + Send + Sync>),
+}
+
+impl Initializer {
+	/// Creates an initializer that computes every weight with the given
+	/// function of the fan-in and fan-out of the layer.
+	pub fn custom<F>(init: F) -> Self
+		where F: Fn(usize, usize) -> f32 + Send + Sync + 'static
+	{
+		Initializer::Custom(Arc::new(init))
+	}
+
+	/// Returns the default weight initializer for layers with the given activation function.
+	///
+	/// This is `HeUniform` for rectifying activation functions
+	/// and `XavierUniform` for all others.
+	pub fn for_activation(activation: Activation) -> Self {
+		use self::Activation::*;
+		match activation {
+			ReLU | SoftPlus => Initializer::HeUniform,
+			_               => Initializer::XavierUniform
+		}
+	}
+
+	/// Draws a weight for a layer with the given fan-in and fan-out.
+	pub(crate) fn sample<R: Rng>(&self, fan_in: usize, fan_out: usize, rng: &mut R) -> f32 {
+		use self::Initializer::*;
+		let (n_in, n_sum) = (fan_in as f64, (fan_in + fan_out) as f64);
+		let uniform = |limit: f64, rng: &mut R| Range::new(-limit, limit).ind_sample(rng) as f32;
+		let normal  = |std_dev: f64, rng: &mut R| distributions::Normal::new(0.0, std_dev).ind_sample(rng) as f32;
+		match *self {
+			Zeros                    => 0.0,
+			Uniform(low, high)       => Range::new(low, high).ind_sample(rng),
+			Normal(mean, std_dev)    => {
+				distributions::Normal::new(f64::from(mean), f64::from(std_dev)).ind_sample(rng) as f32
+			}
+			XavierUniform            => uniform((6.0 / n_sum).sqrt(), rng),
+			XavierNormal             => normal((2.0 / n_sum).sqrt(), rng),
+			HeUniform                => uniform((6.0 / n_in).sqrt(), rng),
+			HeNormal                 => normal((2.0 / n_in).sqrt(), rng),
+			LeCunUniform             => uniform((3.0 / n_in).sqrt(), rng),
+			LeCunNormal              => normal((1.0 / n_in).sqrt(), rng),
+			Custom(ref init)         => init(fan_in, fan_out)
+		}
+	}
+}
+
+impl fmt::Debug for Initializer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		use self::Initializer::*;
+		match *self {
+			Zeros                 => write!(f, "Zeros"),
+			Uniform(low, high)    => write!(f, "Uniform({}, {})", low, high),
+			Normal(mean, std_dev) => write!(f, "Normal({}, {})", mean, std_dev),
+			XavierUniform         => write!(f, "XavierUniform"),
+			XavierNormal          => write!(f, "XavierNormal"),
+			HeUniform             => write!(f, "HeUniform"),
+			HeNormal              => write!(f, "HeNormal"),
+			LeCunUniform          => write!(f, "LeCunUniform"),
+			LeCunNormal           => write!(f, "LeCunNormal"),
+			Custom(_)             => write!(f, "Custom(_)"),
+		}
+	}
+}
+
+/// Custom initializers are only equal if they share the same function.
+impl PartialEq for Initializer {
+	fn eq(&self, other: &Self) -> bool {
+		use self::Initializer::*;
+		match (self, other) {
+			(&Uniform(l1, h1), &Uniform(l2, h2)) => l1 == l2 && h1 == h2,
+			(&Normal(m1, s1), &Normal(m2, s2))   => m1 == m2 && s1 == s2,
+			(Custom(f1), Custom(f2))             => Arc::ptr_eq(f1, f2),
+			(&Zeros, &Zeros)                     |
+			(&XavierUniform, &XavierUniform)     |
+			(&XavierNormal, &XavierNormal)       |
+			(&HeUniform, &HeUniform)             |
+			(&HeNormal, &HeNormal)               |
+			(&LeCunUniform, &LeCunUniform)       |
+			(&LeCunNormal, &LeCunNormal)         => true,
+			_                                    => false
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rng;
+
+	#[test]
+	fn scaled_by_fan() {
+		let mut rng = rng::from_seed(0);
+		let limit = (6.0f32 / 30.0).sqrt();
+		for _ in 0..1000 {
+			let weight = Initializer::XavierUniform.sample(10, 20, &mut rng);
+			assert!(weight.abs() <= limit);
+		}
+		let limit = (6.0f32 / 10.0).sqrt();
+		for _ in 0..1000 {
+			let weight = Initializer::HeUniform.sample(10, 20, &mut rng);
+			assert!(weight.abs() <= limit);
+		}
+		assert_eq!(Initializer::Zeros.sample(10, 20, &mut rng), 0.0);
+	}
+
+	#[test]
+	fn custom() {
+		let mut rng = rng::from_seed(0);
+		let init = Initializer::custom(|fan_in, fan_out| (fan_in * fan_out) as f32);
+		assert_eq!(init.sample(3, 4, &mut rng), 12.0);
+		assert_eq!(init.clone(), init);
+		assert!(Initializer::custom(|_, _| 12.0) != init);
+	}
+
+	#[test]
+	fn default_for_activation() {
+		assert_eq!(Initializer::for_activation(Activation::ReLU), Initializer::HeUniform);
+		assert_eq!(Initializer::for_activation(Activation::Tanh), Initializer::XavierUniform);
+	}
+}
@@ -1,6 +1,10 @@
 #![cfg_attr(all(feature = "benches", test), feature(test))]
 
 #![warn(missing_docs)]
+#![allow(unknown_lints, mismatched_lifetime_syntaxes)]
+#![allow(clippy::tabs_in_doc_comments, clippy::redundant_field_names)]
+#![allow(clippy::doc_lazy_continuation, clippy::derivable_impls)]
+#![cfg_attr(test, allow(clippy::approx_constant))]
 
 //! A neural net implementation focused on sequential performance.
 //! 
@@ -39,12 +43,12 @@
 //! 	.layer(2, Tanh)          // and 2 neurons in the second hidden layer
 //! 	.output(1, Tanh);        // and 1 neuron in the output layer
 //! 
-//! let mut net = top.train(train_samples)
+//! let (mut net, _) = top.train(train_samples)
 //! 	.learn_rate(0.25)    // use the given learn rate
 //! 	.learn_momentum(0.6) // use the given learn momentum
 //! 	.log_config(LogConfig::Iterations(100)) // log state every 100 iterations
 //! 	.scheduling(Scheduling::Random)         // use random sample scheduling
-//! 	.criterion(Criterion::RecentMSE(0.05))  // train until the recent MSE is below 0.05
+//! 	.criterion(Criterion::RecentLoss(0.05)) // train until the recent loss is below 0.05
 //! 
 //! 	.go()      // start the training session
 //! 	.unwrap(); // be ashamed to unwrap a Result
@@ -68,7 +72,7 @@
 //! # fn main() {
 //! # let (t, f)  = (1.0, -1.0);
 //! // create the topology for our neural network
-//! let mut net = Topology::input(2) // has two input neurons
+//! let (mut net, _) = Topology::input(2) // has two input neurons
 //! 	.layer(3, Tanh)              // with 3 neurons in the first hidden layer
 //! 	.layer(2, Tanh)              // and 2 neurons in the second hidden layer
 //! 	.output(1, Tanh)             // and 1 neuron in the output layer
@@ -104,6 +108,9 @@ extern crate serde;
 #[cfg(feature = "serde_support")] #[macro_use]
 extern crate serde_derive;
 
+#[cfg(feature = "serde_support")]
+extern crate bincode;
+
 #[macro_use]
 extern crate log;
 
@@ -119,18 +126,41 @@ mod benches;
 mod traits;
 mod neural_net;
 mod activation;
+mod initializer;
+mod optimizer;
 mod errors;
+mod rng;
+mod scaling;
+mod loss;
+mod regularization;
+mod clipping;
 
 pub mod topology;
 mod mentor;
 pub mod prelude;
 
-pub use activation::Activation;
+pub use activation::{Activation, OutputActivation};
+pub use initializer::Initializer;
+pub use scaling::{Scaler, Scaling};
+pub use loss::Loss;
+pub use regularization::Regularization;
+pub use clipping::GradientClipping;
+pub use optimizer::{Optimizer, UpdateRule};
 pub use neural_net::NeuralNet;
 
-pub use mentor::configs::{LogConfig, Scheduling, Criterion};
+pub use mentor::configs::{LogConfig, Scheduling, Criterion, BatchSize};
 pub use mentor::training::{Mentor, MentorBuilder};
 pub use mentor::samples::{Sample, SampleView};
+pub use mentor::source::{SampleSource, MemorySource, FileSource};
+pub use mentor::csv::{CsvLoader, Columns, MissingValues};
+pub use mentor::idx::{load_idx, read_idx};
+pub use mentor::logger::Stats;
+pub use mentor::adapt::LearnRatePolicy;
+pub use mentor::report::{TrainingReport, StopReason};
+pub use mentor::observer::{TrainingObserver, Observation, MetricsWriter, MetricsFormat};
+pub use mentor::logger::{TimeStepsLogger, IterationsLogger};
+pub use mentor::checkpoint::Checkpoint;
+pub use mentor::split::{Split, CrossValidation, FoldReport, k_folds};
 
 pub use traits::{Predict};
 pub use errors::{Result, ErrorKind};
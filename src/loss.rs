@@ -0,0 +1,188 @@
+//! Provides loss functions that measure the error of predictions
+//! and drive the gradients of the output layer during training.
+
+use ndarray::prelude::*;
+
+use activation::{Activation, OutputActivation};
+use errors::ErrorKind::InvalidLoss;
+use errors::Result;
+
+/// Smallest probability used to avoid infinite logarithms in cross-entropy losses.
+const EPSILON: f32 = 1e-7;
+
+/// Represents a loss function of predicted outputs *y* and targets *t*.
+///
+/// The loss of a sample is the mean loss over all of its outputs except
+/// for the categorical cross-entropy which is the sum over all outputs.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum Loss {
+	/// Mean squared error: *(t - y)²*
+	///
+	/// This is the default for all but softmax output layers.
+	#[default]
+	MeanSquaredError,
+
+	/// Mean absolute error: *|t - y|*
+	///
+	/// Less sensitive to outliers than the mean squared error.
+	MeanAbsoluteError,
+
+	/// Huber loss with the given threshold *δ*:
+	/// *½(t - y)²* **if** *|t - y| ≤ δ*
+	/// *δ(|t - y| - ½δ)* **else**
+	///
+	/// Quadratic for small and linear for large errors.
+	Huber(f32),
+
+	/// Binary cross-entropy: *-(t⋅__ln__(y) + (1 - t)⋅__ln__(1 - y))*
+	///
+	/// For binary classification with targets and outputs in *[0, 1]*,
+	/// usually with `Logistic` output layers.
+	BinaryCrossEntropy,
+
+	/// Hinge loss: *__max__(0, 1 - t⋅y)*
+	///
+	/// For binary classification with targets of either *-1* or *1*,
+	/// usually with `Tanh` or `Identity` output layers.
+	Hinge,
+
+	/// Categorical cross-entropy: *-Σ t⋅__ln__(y)*
+	///
+	/// For multi-class classification with one-hot targets.
+	/// Required by and the default for `OutputActivation::SoftMax` output layers.
+	CategoricalCrossEntropy,
+}
+
+impl Loss {
+	/// Returns the default loss for output layers with the given activation function.
+	///
+	/// This is `CategoricalCrossEntropy` for softmax and `MeanSquaredError` for all others.
+	pub fn for_activation<A>(activation: A) -> Self
+		where A: Into<OutputActivation>
+	{
+		match activation.into() {
+			OutputActivation::SoftMax    => Loss::CategoricalCrossEntropy,
+			OutputActivation::Element(_) => Loss::MeanSquaredError
+		}
+	}
+
+	/// Checks if this loss is valid for output layers with the given activation function.
+	///
+	/// Softmax output layers require the categorical cross-entropy and vice versa.
+	pub(crate) fn check_validity(self, activation: OutputActivation) -> Result<()> {
+		let valid_huber = match self {
+			Loss::Huber(delta) => delta > 0.0 && delta.is_finite(),
+			_                  => true
+		};
+		let softmax = activation == OutputActivation::SoftMax;
+		if !valid_huber || softmax != (self == Loss::CategoricalCrossEntropy) {
+			return Err(InvalidLoss)
+		}
+		Ok(())
+	}
+
+	/// Returns the loss of a single output.
+	fn value(self, target: f32, output: f32) -> f64 {
+		use self::Loss::*;
+		let diff  = f64::from(target - output);
+		let clamp = |output: f32| f64::from(output.clamp(EPSILON, 1.0 - EPSILON));
+		match self {
+			MeanSquaredError        => diff * diff,
+			MeanAbsoluteError       => diff.abs(),
+			Huber(delta)            => {
+				let delta = f64::from(delta);
+				if diff.abs() <= delta { 0.5 * diff * diff } else { delta * (diff.abs() - 0.5 * delta) }
+			}
+			BinaryCrossEntropy      => {
+				let (target, output) = (f64::from(target), clamp(output));
+				-(target * output.ln() + (1.0 - target) * (1.0 - output).ln())
+			}
+			Hinge                   => (1.0 - f64::from(target * output)).max(0.0),
+			CategoricalCrossEntropy => -f64::from(target) * f64::from(output.max(EPSILON)).ln()
+		}
+	}
+
+	/// Calculates the loss of the given actual outputs for the given expected targets.
+	pub fn error(self, actual: ArrayView1<f32>, expected: ArrayView1<f32>) -> f64 {
+		let sum = actual
+			.iter()
+			.zip(expected.iter())
+			.map(|(&output, &target)| self.value(target, output))
+			.sum::<f64>();
+		match self {
+			Loss::CategoricalCrossEntropy => sum,
+			_                             => sum / actual.len() as f64
+		}
+	}
+
+	/// Calculates the output gradient for the back propagation phase, i.e. the
+	/// negative derivative of this loss with respect to the input of an output
+	/// neuron with the given activation function, target and output.
+	///
+	/// Softmax with categorical cross-entropy and logistic with binary
+	/// cross-entropy use their fused gradient *t - y*.
+	pub(crate) fn gradient(self, activation: Activation, target: f32, output: f32) -> f32 {
+		use self::Loss::*;
+		let diff = target - output;
+		match (self, activation) {
+			(CategoricalCrossEntropy, _) |
+			(BinaryCrossEntropy, Activation::Logistic) => return diff,
+			_ => ()
+		}
+		let derivative = match self {
+			MeanSquaredError        => diff,
+			MeanAbsoluteError       => if diff > 0.0 { 1.0 } else if diff < 0.0 { -1.0 } else { 0.0 },
+			Huber(delta)            => diff.max(-delta).min(delta),
+			BinaryCrossEntropy      => {
+				let output = output.clamp(EPSILON, 1.0 - EPSILON);
+				diff / (output * (1.0 - output))
+			}
+			Hinge                   => if target * output < 1.0 { target } else { 0.0 },
+			CategoricalCrossEntropy => diff
+		};
+		derivative * activation.derived(output)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn errors() {
+		let (actual, expected) = (aview1(&[0.5, 1.0]), aview1(&[1.0, -1.0]));
+		assert_relative_eq!(Loss::MeanSquaredError.error(actual, expected), 2.125);
+		assert_relative_eq!(Loss::MeanAbsoluteError.error(actual, expected), 1.25);
+		assert_relative_eq!(Loss::Huber(1.0).error(actual, expected), (0.125 + 1.5) / 2.0);
+		assert_relative_eq!(Loss::Hinge.error(actual, expected), (0.5 + 2.0) / 2.0);
+		let (actual, expected) = (aview1(&[0.25, 0.75]), aview1(&[0.0, 1.0]));
+		assert_relative_eq!(Loss::CategoricalCrossEntropy.error(actual, expected), -(0.75f64).ln(), epsilon = 1e-6);
+		assert_relative_eq!(Loss::BinaryCrossEntropy.error(actual, expected), -(0.75f64).ln(), epsilon = 1e-6);
+	}
+
+	#[test]
+	fn gradients() {
+		use self::Activation::{Identity, Logistic};
+		assert_eq!(Loss::MeanSquaredError.gradient(Identity, 1.0, 0.25), 0.75);
+		assert_eq!(Loss::MeanAbsoluteError.gradient(Identity, 1.0, 3.0), -1.0);
+		assert_eq!(Loss::Huber(0.5).gradient(Identity, 1.0, 0.0), 0.5);
+		assert_eq!(Loss::Huber(0.5).gradient(Identity, 1.0, 0.75), 0.25);
+		assert_eq!(Loss::Hinge.gradient(Identity, -1.0, 0.5), -1.0);
+		assert_eq!(Loss::Hinge.gradient(Identity, 1.0, 2.0), 0.0);
+		assert_eq!(Loss::BinaryCrossEntropy.gradient(Logistic, 1.0, 0.25), 0.75);
+		assert_eq!(Loss::CategoricalCrossEntropy.gradient(Identity, 0.0, 0.25), -0.25);
+	}
+
+	#[test]
+	fn validity() {
+		use self::OutputActivation::SoftMax;
+		let tanh = OutputActivation::Element(Activation::Tanh);
+		assert_eq!(Loss::for_activation(SoftMax), Loss::CategoricalCrossEntropy);
+		assert_eq!(Loss::for_activation(Activation::Tanh), Loss::MeanSquaredError);
+		assert_eq!(Loss::Hinge.check_validity(tanh), Ok(()));
+		assert_eq!(Loss::Huber(0.0).check_validity(tanh), Err(InvalidLoss));
+		assert_eq!(Loss::MeanSquaredError.check_validity(SoftMax), Err(InvalidLoss));
+		assert_eq!(Loss::CategoricalCrossEntropy.check_validity(tanh), Err(InvalidLoss));
+	}
+}
@@ -0,0 +1,317 @@
+//! Heuristics that adapt the learning parameters during a training session.
+
+use traits::{LearnRate, LearnMomentum};
+use mentor::deviation::Deviation;
+use mentor::configs::LearnRateConfig;
+
+/// Minimum number of iterations between two adaptions.
+const MIN_ADAPT_INTERVAL: u64 = 100;
+
+/// Relative tolerance before a growing error counts as regression.
+///
+/// This prevents the noise of randomly scheduled samples
+/// from steadily shrinking the learning parameters.
+const REGRESSION_TOLERANCE: f64 = 0.1;
+
+/// Factor by which the learn rate grows after the error improved.
+const RATE_INCREASE: f32 = 1.1;
+
+/// Factor by which the learn rate shrinks after the error regressed.
+const RATE_DECREASE: f32 = 0.5;
+
+/// Bounds within which the adapted learn rate is kept.
+const RATE_MIN: f32 = 0.001;
+const RATE_MAX: f32 = 0.9;
+
+/// Fraction of the remaining distance to `MOMENTUM_MAX` the
+/// learn momentum grows after the error improved.
+const MOMENTUM_GROWTH: f32 = 0.1;
+
+/// Factor by which the learn momentum shrinks after the error regressed.
+const MOMENTUM_DECREASE: f32 = 0.5;
+
+/// Bounds within which the adapted learn momentum is kept.
+const MOMENTUM_MIN: f32 = 0.01;
+const MOMENTUM_MAX: f32 = 0.9;
+
+/// The development of the error between two adaptions.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Trend {
+	/// The error has decreased.
+	Improved,
+
+	/// The error has not changed noticeably.
+	Stagnated,
+
+	/// The error has grown noticeably or is no longer finite.
+	Regressed,
+}
+
+/// Tracks the development of the error over intervals of iterations.
+///
+/// The latest losses are averaged over intervals of one
+/// pass over all samples (but at least `MIN_ADAPT_INTERVAL` iterations)
+/// and compared with the average of the previous interval.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+struct ErrorTrend {
+	interval     : u64,
+	last_avg_loss: f64,
+	acc_loss     : f64,
+	count        : u64,
+}
+
+impl ErrorTrend {
+	/// Creates a new error trend for a training session
+	/// over the given number of samples.
+	fn new(num_samples: usize) -> Self {
+		ErrorTrend{
+			interval     : ::std::cmp::max(num_samples as u64, MIN_ADAPT_INTERVAL),
+			last_avg_loss: f64::INFINITY,
+			acc_loss     : 0.0,
+			count        : 0
+		}
+	}
+
+	/// Accounts the latest error of the given deviation and returns
+	/// the trend of the error whenever an interval has been completed.
+	fn update(&mut self, deviation: &Deviation) -> Option<Trend> {
+		self.acc_loss += deviation.latest_loss();
+		self.count   += 1;
+		if self.count < self.interval {
+			return None
+		}
+
+		let avg_loss = self.acc_loss / self.count as f64;
+		let trend = if avg_loss < self.last_avg_loss {
+			Trend::Improved
+		} else if avg_loss <= self.last_avg_loss * (1.0 + REGRESSION_TOLERANCE) {
+			Trend::Stagnated
+		} else {
+			// Also reached for non-finite errors.
+			Trend::Regressed
+		};
+		self.last_avg_loss = avg_loss;
+		self.acc_loss      = 0.0;
+		self.count         = 0;
+		Some(trend)
+	}
+}
+
+/// The policy by which the learn rate is chosen during training.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum LearnRatePolicy {
+	/// The learn rate is fixed to the value given by the user.
+	Fixed,
+
+	/// The learn rate is adapted with the *bold driver* heuristic.
+	BoldDriver{
+		/// Factor by which the learn rate grows after the error improved.
+		increase: f32,
+
+		/// Factor by which the learn rate shrinks after the error regressed.
+		decrease: f32,
+	},
+}
+
+impl LearnRateConfig {
+	/// Returns the policy by which the learn rate is chosen with this configuration.
+	pub fn policy(self) -> LearnRatePolicy {
+		match self {
+			LearnRateConfig::Adapt    => LearnRatePolicy::BoldDriver{
+				increase: RATE_INCREASE,
+				decrease: RATE_DECREASE,
+			},
+			LearnRateConfig::Fixed(_) => LearnRatePolicy::Fixed
+		}
+	}
+
+	/// Returns the largest learn rate that may be used with this configuration.
+	pub fn max_rate(self) -> LearnRate {
+		match self {
+			LearnRateConfig::Adapt       => LearnRate(RATE_MAX),
+			LearnRateConfig::Fixed(rate) => rate
+		}
+	}
+}
+
+/// Adapts the learn rate with the *bold driver* heuristic.
+///
+/// The learn rate is slightly increased if the error has improved
+/// since the last adaption and halved if the error has grown noticeably.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct LearnRateAdapter {
+	trend: ErrorTrend,
+}
+
+impl LearnRateAdapter {
+	/// Creates a new learn rate adapter for a training session
+	/// over the given number of samples.
+	pub fn new(num_samples: usize) -> Self {
+		LearnRateAdapter{
+			trend: ErrorTrend::new(num_samples)
+		}
+	}
+
+	/// Returns the adapted learn rate based on the given current learn rate
+	/// and the latest deviation.
+	pub fn adapt(&mut self, rate: LearnRate, deviation: &Deviation) -> LearnRate {
+		let factor = match self.trend.update(deviation) {
+			Some(Trend::Improved)  => RATE_INCREASE,
+			Some(Trend::Regressed) => RATE_DECREASE,
+			Some(Trend::Stagnated) |
+			None                   => return rate
+		};
+		LearnRate((rate.0 * factor).clamp(RATE_MIN, RATE_MAX))
+	}
+}
+
+/// Adapts the learn momentum along with the error trend.
+///
+/// While the error keeps improving the learn momentum gradually approaches
+/// `MOMENTUM_MAX` to speed up convergence.
+/// As soon as the error grows noticeably the learn momentum is halved
+/// to no longer push the weights into the wrong direction.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct LearnMomentumAdapter {
+	trend: ErrorTrend,
+}
+
+impl LearnMomentumAdapter {
+	/// Creates a new learn momentum adapter for a training session
+	/// over the given number of samples.
+	pub fn new(num_samples: usize) -> Self {
+		LearnMomentumAdapter{
+			trend: ErrorTrend::new(num_samples)
+		}
+	}
+
+	/// Returns the adapted learn momentum based on the given current learn momentum
+	/// and the latest deviation.
+	pub fn adapt(&mut self, momentum: LearnMomentum, deviation: &Deviation) -> LearnMomentum {
+		let adapted = match self.trend.update(deviation) {
+			Some(Trend::Improved)  => momentum.0 + (MOMENTUM_MAX - momentum.0) * MOMENTUM_GROWTH,
+			Some(Trend::Regressed) => momentum.0 * MOMENTUM_DECREASE,
+			Some(Trend::Stagnated) |
+			None                   => return momentum
+		};
+		LearnMomentum(adapted.clamp(MOMENTUM_MIN, MOMENTUM_MAX))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use ndarray::prelude::*;
+	use loss::Loss;
+
+	fn deviation_with_latest_loss(loss: f32) -> Deviation {
+		let mut deviation = Deviation::default();
+		deviation.update(
+			Loss::MeanAbsoluteError,
+			Array1::from_vec(vec![0.0]).view(),
+			Array1::from_vec(vec![loss]).view(),
+			0.0);
+		deviation
+	}
+
+	fn adapt_rate_interval(adapter: &mut LearnRateAdapter, rate: LearnRate, loss: f32) -> LearnRate {
+		let deviation = deviation_with_latest_loss(loss);
+		let mut rate = rate;
+		for _ in 0..adapter.trend.interval {
+			rate = adapter.adapt(rate, &deviation);
+		}
+		rate
+	}
+
+	fn adapt_momentum_interval(adapter: &mut LearnMomentumAdapter, momentum: LearnMomentum, loss: f32) -> LearnMomentum {
+		let deviation = deviation_with_latest_loss(loss);
+		let mut momentum = momentum;
+		for _ in 0..adapter.trend.interval {
+			momentum = adapter.adapt(momentum, &deviation);
+		}
+		momentum
+	}
+
+	#[test]
+	fn trend() {
+		let mut trend = ErrorTrend::new(1);
+		let deviation = deviation_with_latest_loss(0.5);
+		for _ in 1..MIN_ADAPT_INTERVAL {
+			assert_eq!(trend.update(&deviation), None);
+		}
+		assert_eq!(trend.update(&deviation), Some(Trend::Improved));
+	}
+
+	#[test]
+	fn rate_policy() {
+		assert_eq!(LearnRateConfig::Fixed(LearnRate(0.3)).policy(), LearnRatePolicy::Fixed);
+		assert_eq!(LearnRateConfig::Adapt.policy(),
+			LearnRatePolicy::BoldDriver{increase: RATE_INCREASE, decrease: RATE_DECREASE});
+	}
+
+	#[test]
+	fn rate_increases_on_improvement() {
+		let mut adapter = LearnRateAdapter::new(4);
+		let rate = adapt_rate_interval(&mut adapter, LearnRate(0.3), 0.5);
+		let rate = adapt_rate_interval(&mut adapter, rate, 0.4);
+		assert!(rate.0 > 0.3);
+	}
+
+	#[test]
+	fn rate_decreases_on_regression() {
+		let mut adapter = LearnRateAdapter::new(4);
+		let rate = adapt_rate_interval(&mut adapter, LearnRate(0.3), 0.2);
+		let rate = adapt_rate_interval(&mut adapter, rate, 0.8);
+		assert!(rate.0 < 0.3);
+	}
+
+	#[test]
+	fn rate_stays_within_bounds() {
+		let mut adapter = LearnRateAdapter::new(4);
+		let mut rate = LearnRate(0.3);
+		let mut loss = 0.9;
+		for _ in 0..100 {
+			loss *= 0.9;
+			rate = adapt_rate_interval(&mut adapter, rate, loss);
+		}
+		assert!(rate.0 <= RATE_MAX);
+	}
+
+	#[test]
+	fn momentum_increases_on_improvement() {
+		let mut adapter = LearnMomentumAdapter::new(4);
+		let momentum = adapt_momentum_interval(&mut adapter, LearnMomentum(0.5), 0.5);
+		let momentum = adapt_momentum_interval(&mut adapter, momentum, 0.4);
+		assert!(momentum.0 > 0.5);
+	}
+
+	#[test]
+	fn momentum_decreases_on_regression() {
+		let mut adapter = LearnMomentumAdapter::new(4);
+		let momentum = adapt_momentum_interval(&mut adapter, LearnMomentum(0.5), 0.2);
+		let momentum = adapt_momentum_interval(&mut adapter, momentum, 0.8);
+		assert!(momentum.0 < 0.5);
+	}
+
+	#[test]
+	fn momentum_stays_within_bounds() {
+		let mut adapter = LearnMomentumAdapter::new(4);
+		let mut momentum = LearnMomentum(0.5);
+		let mut loss = 0.9;
+		for _ in 0..100 {
+			loss *= 0.9;
+			momentum = adapt_momentum_interval(&mut adapter, momentum, loss);
+		}
+		assert!(momentum.0 <= MOMENTUM_MAX);
+		for _ in 0..100 {
+			loss *= 1.2;
+			momentum = adapt_momentum_interval(&mut adapter, momentum, loss);
+		}
+		assert!(momentum.0 >= MOMENTUM_MIN);
+	}
+}
@@ -0,0 +1,298 @@
+//! Checkpoints persist the full state of a training session to disk
+//! so that an interrupted training can be resumed where it stopped.
+
+#[cfg(feature = "serde_support")]
+use std::fs;
+#[cfg(feature = "serde_support")]
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[cfg(feature = "serde_support")]
+use bincode::{self, Options};
+use neural_net::NeuralNet;
+use traits::{LearnRate, LearnMomentum};
+#[cfg(feature = "serde_support")]
+use errors::ErrorKind::{CheckpointIo, InvalidCheckpoint};
+#[cfg(feature = "serde_support")]
+use errors::Result;
+use mentor::deviation::Deviation;
+use mentor::adapt::{LearnRateAdapter, LearnMomentumAdapter};
+use mentor::validation::ValidationProgress;
+use mentor::report::History;
+use mentor::samples::SchedulerState;
+use rng::Prng;
+
+/// Maximum number of rollbacks within a training session.
+const MAX_ROLLBACKS: u32 = 10;
+
+/// Factor by which the learn rate shrinks with every rollback.
+const ROLLBACK_RATE_DECREASE: f32 = 0.5;
+
+/// Identifies checkpoint files and their format version.
+///
+/// The checkpoint itself follows in the `bincode` format.
+#[cfg(feature = "serde_support")]
+const MAGIC: &[u8] = b"PROPHET-CHECKPOINT-2\n";
+
+/// The full state of a training session at a given iteration.
+///
+/// Checkpoints are written periodically during training if configured
+/// via `Mentor::checkpoint` and can be loaded to resume the training
+/// with `Checkpoint::train`.
+///
+/// Training samples are not part of a checkpoint and have to be
+/// provided again when resuming.
+///
+/// Checkpoints can only be written and loaded with the `serde_support` feature.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Checkpoint {
+	pub(crate) net     : NeuralNet,
+	pub(crate) progress: Progress,
+}
+
+/// The progress of a training session besides the neural network itself.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Progress {
+	pub iterations      : u64,
+	pub elapsed_time    : Duration,
+	pub deviation       : Deviation,
+	pub learn_rate      : LearnRate,
+	pub learn_mom       : LearnMomentum,
+	pub rate_adapter    : LearnRateAdapter,
+	pub momentum_adapter: LearnMomentumAdapter,
+	pub scheduler       : SchedulerState,
+	pub dropout         : Option<Prng>,
+	pub validator       : Option<ValidationProgress>,
+	pub history         : Option<History>,
+}
+
+impl Checkpoint {
+	/// Loads a checkpoint from the file at the given path.
+	#[cfg(feature = "serde_support")]
+	pub fn load<P: AsRef<Path>>(path: P) -> Result<Checkpoint> {
+		let bytes = fs::read(path).map_err(|_| CheckpointIo)?;
+		if !bytes.starts_with(MAGIC) {
+			return Err(InvalidCheckpoint)
+		}
+		let encoded = &bytes[MAGIC.len()..];
+		bincode::options()
+			.with_limit(encoded.len() as u64)
+			.deserialize(encoded)
+			.map_err(|_| InvalidCheckpoint)
+	}
+
+	/// Saves this checkpoint to the file at the given path.
+	///
+	/// The checkpoint is written to a temporary file first that then
+	/// replaces the given file, so an interruption never leaves a
+	/// partially written checkpoint behind.
+	#[cfg(feature = "serde_support")]
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+		let path = path.as_ref();
+		let mut bytes = MAGIC.to_vec();
+		bytes.extend(bincode::options().serialize(self).map_err(|_| InvalidCheckpoint)?);
+		let mut temp = path.as_os_str().to_owned();
+		temp.push(".tmp");
+		fs::write(&temp, bytes).map_err(|_| CheckpointIo)?;
+		fs::rename(&temp, path).map_err(|_| CheckpointIo)
+	}
+
+	/// Returns the number of training iterations until this checkpoint.
+	pub fn iterations(&self) -> u64 {
+		self.progress.iterations
+	}
+
+	/// Returns the neural network at the state of this checkpoint.
+	pub fn net(&self) -> &NeuralNet {
+		&self.net
+	}
+}
+
+/// Writes checkpoints every given number of training iterations.
+#[cfg(feature = "serde_support")]
+#[derive(Debug, Clone)]
+pub struct Checkpointer {
+	path     : PathBuf,
+	interval : u64,
+	next_save: u64,
+}
+
+#[cfg(feature = "serde_support")]
+impl Checkpointer {
+	/// Creates a new checkpointer writing to the given path every
+	/// time the given amount of iterations has passed.
+	pub fn new(path: PathBuf, interval: u64) -> Self {
+		Checkpointer{
+			path     : path,
+			interval : interval,
+			next_save: interval,
+		}
+	}
+
+	/// Returns a checkpointer for the given fold of a cross validation
+	/// writing to the path of this checkpointer suffixed with `.fold-<fold>`.
+	pub fn for_fold(&self, fold: usize) -> Self {
+		let mut path = self.path.as_os_str().to_owned();
+		path.push(format!(".fold-{}", fold));
+		Checkpointer::new(path.into(), self.interval)
+	}
+
+	/// Returns `true` if the checkpoint interval is valid.
+	pub fn is_valid(&self) -> bool {
+		self.interval > 0
+	}
+
+	/// Skips all checkpoints up to the given iteration.
+	pub fn skip_to(&mut self, iterations: u64) {
+		while self.next_save <= iterations {
+			self.next_save += self.interval;
+		}
+	}
+
+	/// Returns `true` if the given iteration has reached the next checkpoint.
+	pub fn is_due(&self, iterations: u64) -> bool {
+		iterations >= self.next_save
+	}
+
+	/// Saves the given checkpoint.
+	pub fn save(&mut self, checkpoint: &Checkpoint) -> Result<()> {
+		self.skip_to(checkpoint.iterations());
+		checkpoint.save(&self.path)
+	}
+}
+
+/// Keeps the latest checkpoint of a training session in memory every given
+/// number of training iterations to roll back to after the training diverged.
+///
+/// Checkpoints are only kept while all values of the neural network are finite.
+#[derive(Debug, Clone)]
+pub struct Rollback {
+	interval : u64,
+	next_save: u64,
+	latest   : Option<Checkpoint>,
+	rollbacks: u32,
+}
+
+impl Rollback {
+	/// Creates a new rollback keeping a checkpoint at the start of the
+	/// training and every time the given amount of iterations has passed.
+	pub fn new(interval: u64) -> Self {
+		Rollback{
+			interval : interval,
+			next_save: 0,
+			latest   : None,
+			rollbacks: 0,
+		}
+	}
+
+	/// Returns `true` if the given iteration has reached the next checkpoint.
+	pub fn is_due(&self, iterations: u64) -> bool {
+		iterations >= self.next_save
+	}
+
+	/// Keeps the given checkpoint as the latest one.
+	pub fn save(&mut self, checkpoint: Checkpoint) {
+		while self.next_save <= checkpoint.iterations() {
+			self.next_save += self.interval;
+		}
+		self.latest = Some(checkpoint)
+	}
+
+	/// Returns the latest checkpoint to roll back to or `None` if
+	/// there is none or the maximum number of rollbacks has been reached.
+	pub fn roll_back(&mut self) -> Option<Checkpoint> {
+		if self.latest.is_none() || self.rollbacks >= MAX_ROLLBACKS {
+			return None
+		}
+		self.rollbacks += 1;
+		self.latest.clone()
+	}
+
+	/// Returns the reduced learn rate to continue with after a rollback
+	/// from the given learn rate the training diverged with.
+	pub fn reduced(rate: LearnRate) -> LearnRate {
+		LearnRate(rate.0 * ROLLBACK_RATE_DECREASE)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	#[cfg(feature = "serde_support")]
+	use std::env;
+	use topology::*;
+	use activation::Activation::Tanh;
+	#[cfg(feature = "serde_support")]
+	use traits::Predict;
+
+	fn checkpoint(iterations: u64) -> Checkpoint {
+		Checkpoint{
+			net: NeuralNet::from_topology(Topology::input(2).layer(3, Tanh).output(1, Tanh)),
+			progress: Progress{
+				iterations      : iterations,
+				elapsed_time    : Duration::from_millis(1234),
+				deviation       : Deviation::default(),
+				learn_rate      : LearnRate(0.25),
+				learn_mom       : LearnMomentum(0.5),
+				rate_adapter    : LearnRateAdapter::new(4),
+				momentum_adapter: LearnMomentumAdapter::new(4),
+				scheduler       : SchedulerState::Iterative(7),
+				dropout         : None,
+				validator       : None,
+				history         : History::new(Some(10)),
+			}
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "serde_support")]
+	fn save_and_load() {
+		let path = env::temp_dir().join(format!("prophet-checkpoint-{}", ::std::process::id()));
+		let saved = checkpoint(42);
+		saved.save(&path).unwrap();
+		let loaded = Checkpoint::load(&path).unwrap();
+		fs::remove_file(&path).unwrap();
+		assert_eq!(loaded.iterations(), 42);
+		assert_eq!(loaded.progress.elapsed_time, saved.progress.elapsed_time);
+		assert_eq!(loaded.progress.learn_rate, saved.progress.learn_rate);
+		assert_eq!(loaded.progress.scheduler, SchedulerState::Iterative(7));
+		let input = [0.5, -0.5];
+		let (mut loaded_net, mut saved_net) = (loaded.net, saved.net);
+		assert_eq!(loaded_net.predict(&input[..]), saved_net.predict(&input[..]));
+	}
+
+	#[test]
+	#[cfg(feature = "serde_support")]
+	fn load_invalid() {
+		let path = env::temp_dir().join(format!("prophet-invalid-checkpoint-{}", ::std::process::id()));
+		fs::write(&path, b"no checkpoint").unwrap();
+		assert_eq!(Checkpoint::load(&path).unwrap_err(), InvalidCheckpoint);
+		checkpoint(42).save(&path).unwrap();
+		let mut truncated = fs::read(&path).unwrap();
+		truncated.truncate(truncated.len() - 1);
+		fs::write(&path, truncated).unwrap();
+		let result = Checkpoint::load(&path);
+		fs::remove_file(&path).unwrap();
+		assert_eq!(result.unwrap_err(), InvalidCheckpoint);
+		assert_eq!(Checkpoint::load(&path).unwrap_err(), CheckpointIo);
+	}
+
+	#[test]
+	fn rolls_back_to_latest() {
+		let mut rollback = Rollback::new(10);
+		assert!(rollback.roll_back().is_none());
+		assert!(rollback.is_due(0));
+		rollback.save(checkpoint(0));
+		assert!(!rollback.is_due(9));
+		assert!(rollback.is_due(10));
+		rollback.save(checkpoint(12));
+		assert!(!rollback.is_due(19));
+		for _ in 0..MAX_ROLLBACKS {
+			assert_eq!(rollback.roll_back().map(|checkpoint| checkpoint.iterations()), Some(12));
+		}
+		assert!(rollback.roll_back().is_none());
+		assert_eq!(Rollback::reduced(LearnRate(0.25)), LearnRate(0.125));
+	}
+}
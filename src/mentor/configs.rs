@@ -1,12 +1,13 @@
 
 use std::time::{Duration};
 
-use errors::ErrorKind::{InvalidLatestMSE, InvalidRecentMSE};
+use errors::ErrorKind::{InvalidLatestLoss, InvalidRecentLoss, InvalidBatchSize, InvalidPatience, EmptyCriterion};
 use errors::Result;
 use traits::{LearnRate, LearnMomentum};
+use mentor::samples::Sample;
 
 /// Cirterias after which the learning process holds.
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Criterion {
 	/// Stop after the given duration of time.
 	TimeOut(Duration),
@@ -14,33 +15,112 @@ pub enum Criterion {
 	/// Stop after the given amount of learning iterations.
 	Iterations(u64),
 
-	/// Stop when the latest mean square error drops below the given value.
-	LatestMSE(f64),
+	/// Stop after the given amount of epochs.
+	///
+	/// An epoch has passed every time as many samples have been
+	/// learned as there are training samples.
+	Epochs(u64),
+
+	/// Stop when the latest loss drops below the given value.
+	LatestLoss(f64),
 
-	/// Stop as soon as the recent mean squared error
+	/// Stop as soon as the recent loss
 	/// drops below the given value.
-	RecentMSE(f64),
+	RecentLoss(f64),
+
+	/// Stop when the loss on the validation samples
+	/// has not noticeably improved for the given amount of evaluations in a row.
+	///
+	/// Requires validation samples to be set.
+	EarlyStopping(u64),
+
+	/// Stop as soon as any of the given criteria is met.
+	Any(Vec<Criterion>),
+
+	/// Stop as soon as all of the given criteria are met at the same time.
+	All(Vec<Criterion>),
 }
 
+#[allow(non_snake_case)]
 impl Criterion {
+	/// Stop when the latest loss drops below the given value.
+	#[deprecated(note = "renamed to `Criterion::LatestLoss` which compares the mean squared error instead of its root by default")]
+	pub fn LatestMSE(latest: f64) -> Criterion {
+		Criterion::LatestLoss(latest)
+	}
+
+	/// Stop as soon as the recent loss drops below the given value.
+	#[deprecated(note = "renamed to `Criterion::RecentLoss` which compares the mean squared error instead of its root by default")]
+	pub fn RecentMSE(recent: f64) -> Criterion {
+		Criterion::RecentLoss(recent)
+	}
+
+	/// Combines this and the given criterion so that
+	/// the learning process holds if any of them is met.
+	pub fn or(self, other: Criterion) -> Criterion {
+		use self::Criterion::*;
+		match self {
+			Any(mut criteria) => {
+				criteria.push(other);
+				Any(criteria)
+			}
+			criterion => Any(vec![criterion, other])
+		}
+	}
+
+	/// Combines this and the given criterion so that
+	/// the learning process holds only if all of them are met.
+	pub fn and(self, other: Criterion) -> Criterion {
+		use self::Criterion::*;
+		match self {
+			All(mut criteria) => {
+				criteria.push(other);
+				All(criteria)
+			}
+			criterion => All(vec![criterion, other])
+		}
+	}
+
+	/// Returns `true` if this criterion or any of its sub-criteria
+	/// depends on validation samples.
+	pub fn requires_validation(&self) -> bool {
+		use self::Criterion::*;
+		match *self {
+			EarlyStopping(_) => true,
+			Any(ref criteria) |
+			All(ref criteria) => criteria.iter().any(Criterion::requires_validation),
+			_ => false
+		}
+	}
+
 	/// Checks if this criterion is valid.
 	pub fn check_validity(&self) -> Result<()> {
 		use self::Criterion::*;
 		match *self {
+			Any(ref criteria) |
+			All(ref criteria) => {
+				if criteria.is_empty() {
+					return Err(EmptyCriterion)
+				}
+				criteria.iter().try_for_each(Criterion::check_validity)
+			}
 			TimeOut(_)    |
-			Iterations(_) => Ok(()),
-			LatestMSE(mse) => {
-				if mse > 0.0 && mse < 1.0 {
+			Iterations(_) |
+			Epochs(_)     => Ok(()),
+			EarlyStopping(0) => Err(InvalidPatience),
+			EarlyStopping(_) => Ok(()),
+			LatestLoss(latest) => {
+				if latest > 0.0 && latest < 1.0 {
 					Ok(())
 				} else {
-					Err(InvalidLatestMSE)
+					Err(InvalidLatestLoss)
 				}
 			}
-			RecentMSE(recent) => {
+			RecentLoss(recent) => {
 				if recent > 0.0 && recent < 1.0 {
 					Ok(())
 				} else {
-					Err(InvalidRecentMSE)
+					Err(InvalidRecentLoss)
 				}
 			}
 		}
@@ -68,6 +148,9 @@ pub enum LearnMomentumConfig {
 }
 
 /// Logging interval for logging stats during the learning process.
+///
+/// Stats are logged via the `log` crate by the built-in
+/// `TimeStepsLogger` and `IterationsLogger` observers.
 /// 
 /// Default logging configuration is to never log anything.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -89,7 +172,9 @@ impl Default for LogConfig {
 }
 
 /// Sample scheduling strategy while learning.
-#[derive(Debug, Copy, Clone, PartialEq)]
+///
+/// `Stratified` strategies compare equal if they share the same label function.
+#[derive(Debug, Copy, Clone)]
 pub enum Scheduling {
 	/// Pick samples randomly.
 	///
@@ -100,4 +185,104 @@ pub enum Scheduling {
 	///
 	/// This maybe useful for testing purposes.
 	Iterative,
+
+	/// Pick every sample exactly once per epoch in a random order
+	/// that is shuffled anew for every epoch.
+	Shuffled,
+
+	/// Pick samples randomly with a probability proportional to their weight.
+	///
+	/// Samples with a weight of zero are never picked.
+	/// See `Sample::with_weight`.
+	Weighted,
+
+	/// Pick samples randomly so that every class is picked equally often.
+	///
+	/// The given function returns the class label of a sample.
+	/// Within their class samples are picked uniformly.
+	///
+	/// This prevents minority classes of imbalanced samples from
+	/// being drowned out in training.
+	Stratified(fn(&Sample) -> usize),
+}
+
+impl PartialEq for Scheduling {
+	fn eq(&self, other: &Self) -> bool {
+		use self::Scheduling::*;
+		match (*self, *other) {
+			(Random, Random)                   |
+			(Iterative, Iterative)             |
+			(Shuffled, Shuffled)               |
+			(Weighted, Weighted)               => true,
+			(Stratified(lhs), Stratified(rhs)) => lhs as usize == rhs as usize,
+			_                                  => false
+		}
+	}
+}
+
+/// Number of samples whose gradients are accumulated before
+/// the weights are updated once with their average.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BatchSize {
+	/// Update the weights after every given number of scheduled samples.
+	///
+	/// A batch size of `1` results in pure stochastic gradient descent.
+	Mini(usize),
+
+	/// Update the weights once per pass over all samples.
+	///
+	/// The scheduling strategy is of no importance for full batches.
+	Full,
+}
+
+impl Default for BatchSize {
+	fn default() -> Self {
+		BatchSize::Mini(1)
+	}
+}
+
+impl BatchSize {
+	/// Checks if this batch size is valid.
+	pub fn check_validity(&self) -> Result<()> {
+		match *self {
+			BatchSize::Mini(0) => Err(InvalidBatchSize),
+			_                  => Ok(())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use errors::ErrorKind::InvalidRecentLoss;
+
+	#[test]
+	fn combine_criteria() {
+		use self::Criterion::*;
+		let any = Iterations(10).or(RecentLoss(0.1)).or(LatestLoss(0.1));
+		assert_eq!(any, Any(vec![Iterations(10), RecentLoss(0.1), LatestLoss(0.1)]));
+		let all = Iterations(10).and(RecentLoss(0.1));
+		assert_eq!(all, All(vec![Iterations(10), RecentLoss(0.1)]));
+		let nested = all.clone().or(EarlyStopping(5));
+		assert_eq!(nested, Any(vec![all, EarlyStopping(5)]));
+		assert!(nested.requires_validation());
+	}
+
+	#[test]
+	fn composite_validity() {
+		use self::Criterion::*;
+		assert_eq!(Any(vec![]).check_validity(), Err(EmptyCriterion));
+		assert_eq!(All(vec![]).check_validity(), Err(EmptyCriterion));
+		assert_eq!(Iterations(10).or(RecentLoss(2.0)).check_validity(), Err(InvalidRecentLoss));
+		assert_eq!(Iterations(10).and(RecentLoss(0.5)).check_validity(), Ok(()));
+	}
+
+	#[test]
+	#[allow(deprecated)]
+	fn deprecated_names() {
+		assert_eq!(Criterion::RecentMSE(0.1), Criterion::RecentLoss(0.1));
+		assert_eq!(Criterion::LatestMSE(0.1), Criterion::LatestLoss(0.1));
+		assert_eq!(Criterion::RecentMSE(2.0).check_validity(), Err(::errors::ErrorKind::InvalidRecentMSE));
+		assert_eq!(Criterion::LatestMSE(0.0).check_validity(), Err(::errors::ErrorKind::InvalidLatestMSE));
+	}
 }
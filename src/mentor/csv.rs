@@ -0,0 +1,352 @@
+//! Loads samples from CSV files.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use errors::ErrorKind::{
+	SampleSourceIo,
+	InvalidCsvColumns,
+	InvalidCsvRowLength,
+	InvalidCsvValue,
+	MissingCsvValue
+};
+use errors::Result;
+use mentor::samples::Sample;
+
+/// Selects the columns of a CSV file that form the inputs or targets of samples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Columns {
+	/// All columns that are not selected for the other part of the samples.
+	Rest,
+
+	/// The given number of last columns.
+	Last(usize),
+
+	/// The columns with the given zero-based indices.
+	Indices(Vec<usize>),
+
+	/// The columns with the given names in the header line.
+	///
+	/// Requires the CSV file to have a header line.
+	Names(Vec<String>),
+}
+
+/// Policy for missing values in CSV files.
+///
+/// Values are missing if they are empty, `NA` or `NaN`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MissingValues {
+	/// Fail with `ErrorKind::MissingCsvValue`.
+	Error,
+
+	/// Skip rows with missing values.
+	SkipRow,
+
+	/// Replace missing values with the given value.
+	Fill(f32),
+}
+
+/// Loads samples from CSV files.
+///
+/// By default the values are separated by commas, there is no header line,
+/// the last column forms the targets and all others form the inputs
+/// of the samples and missing values are an error.
+///
+/// Empty lines are skipped. Values may be enclosed in double quotes
+/// to contain the delimiter where double quotes within are escaped by
+/// doubling them. Quoted values spanning multiple lines are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsvLoader {
+	delimiter: char,
+	header   : bool,
+	inputs   : Columns,
+	targets  : Columns,
+	missing  : MissingValues,
+}
+
+impl Default for CsvLoader {
+	fn default() -> Self {
+		CsvLoader{
+			delimiter: ',',
+			header   : false,
+			inputs   : Columns::Rest,
+			targets  : Columns::Last(1),
+			missing  : MissingValues::Error,
+		}
+	}
+}
+
+impl CsvLoader {
+	/// Creates a new CSV loader with default settings.
+	pub fn new() -> Self {
+		CsvLoader::default()
+	}
+
+	/// Use the given character to separate values.
+	pub fn delimiter(mut self, delimiter: char) -> Self {
+		self.delimiter = delimiter;
+		self
+	}
+
+	/// Treat the first non-empty line as header line with column names.
+	pub fn header(mut self, header: bool) -> Self {
+		self.header = header;
+		self
+	}
+
+	/// Use the given columns as sample inputs.
+	pub fn inputs(mut self, columns: Columns) -> Self {
+		self.inputs = columns;
+		self
+	}
+
+	/// Use the given columns as sample targets.
+	pub fn targets(mut self, columns: Columns) -> Self {
+		self.targets = columns;
+		self
+	}
+
+	/// Use the given policy for missing values.
+	pub fn missing_values(mut self, policy: MissingValues) -> Self {
+		self.missing = policy;
+		self
+	}
+
+	/// Loads all samples from the CSV file at the given path.
+	pub fn load<P: AsRef<Path>>(&self, path: P) -> Result<Vec<Sample>> {
+		let file = File::open(path).map_err(|_| SampleSourceIo)?;
+		self.load_reader(BufReader::new(file))
+	}
+
+	/// Loads all samples from the given CSV text.
+	pub fn load_str(&self, text: &str) -> Result<Vec<Sample>> {
+		self.load_reader(text.as_bytes())
+	}
+
+	/// Loads all samples from the CSV data of the given reader.
+	pub fn load_reader<R: BufRead>(&self, reader: R) -> Result<Vec<Sample>> {
+		let mut header  = None;
+		let mut columns = None;
+		let mut samples = vec![];
+		for (index, line) in reader.lines().enumerate() {
+			let line_no = index as u64 + 1;
+			let line    = line.map_err(|_| SampleSourceIo)?;
+			if line.trim().is_empty() {
+				continue
+			}
+			let fields = split_fields(&line, self.delimiter)
+				.map_err(|column| InvalidCsvValue{line: line_no, column})?;
+			if self.header && header.is_none() {
+				header = Some(fields);
+				continue
+			}
+			if columns.is_none() {
+				let width = header.as_ref().map_or(fields.len(), Vec::len);
+				columns = Some(self.select(header.as_deref(), width)?);
+			}
+			let &(ref inputs, ref targets, width) = columns.as_ref().expect("columns must be selected!");
+			if fields.len() != width {
+				return Err(InvalidCsvRowLength{line: line_no})
+			}
+			let parse = |selected: &[usize]| {
+				selected
+					.iter()
+					.map(|&column| self.parse(&fields[column], line_no, column))
+					.collect::<Result<Option<Vec<f32>>>>()
+			};
+			if let (Some(input), Some(target)) = (parse(inputs)?, parse(targets)?) {
+				samples.push(Sample::new(input, target))
+			}
+		}
+		Ok(samples)
+	}
+
+	/// Parses a value and returns `None` if the row should be skipped.
+	fn parse(&self, field: &str, line: u64, column: usize) -> Result<Option<f32>> {
+		let value = if field.is_empty() || field.eq_ignore_ascii_case("NA") {
+			None
+		}
+		else {
+			match field.parse::<f32>() {
+				Ok(value) if value.is_nan() => None,
+				Ok(value)                   => Some(value),
+				Err(_)                      => return Err(InvalidCsvValue{line: line, column: column})
+			}
+		};
+		match (value, self.missing) {
+			(Some(value), _)                    => Ok(Some(value)),
+			(None, MissingValues::Error)        => Err(MissingCsvValue{line: line, column: column}),
+			(None, MissingValues::SkipRow)      => Ok(None),
+			(None, MissingValues::Fill(value))  => Ok(Some(value))
+		}
+	}
+
+	/// Resolves the input and target column selections for rows of the given width.
+	fn select(&self, header: Option<&[String]>, width: usize) -> Result<(Vec<usize>, Vec<usize>, usize)> {
+		let resolve = |columns: &Columns| -> Result<Option<Vec<usize>>> {
+			match *columns {
+				Columns::Rest => Ok(None),
+				Columns::Last(count) if count <= width => Ok(Some((width - count..width).collect())),
+				Columns::Last(_) => Err(InvalidCsvColumns),
+				Columns::Indices(ref indices) => {
+					if indices.iter().all(|&index| index < width) {
+						Ok(Some(indices.clone()))
+					}
+					else {
+						Err(InvalidCsvColumns)
+					}
+				}
+				Columns::Names(ref names) => {
+					let header = header.ok_or(InvalidCsvColumns)?;
+					names
+						.iter()
+						.map(|name| header.iter().position(|column| column == name).ok_or(InvalidCsvColumns))
+						.collect::<Result<Vec<_>>>()
+						.map(Some)
+				}
+			}
+		};
+		let rest = |selected: &[usize]| (0..width).filter(|column| !selected.contains(column)).collect();
+		let (inputs, targets) = match (resolve(&self.inputs)?, resolve(&self.targets)?) {
+			(Some(inputs), Some(targets)) => (inputs, targets),
+			(Some(inputs), None)          => { let targets = rest(&inputs); (inputs, targets) }
+			(None, Some(targets))         => (rest(&targets), targets),
+			(None, None)                  => return Err(InvalidCsvColumns)
+		};
+		if inputs.is_empty() || targets.is_empty() {
+			return Err(InvalidCsvColumns)
+		}
+		// A column that is both an input and a target would leak the targets.
+		if inputs.iter().any(|column| targets.contains(column)) {
+			return Err(InvalidCsvColumns)
+		}
+		Ok((inputs, targets, width))
+	}
+}
+
+/// Splits the given line into its fields separated by the given delimiter.
+///
+/// Surrounding whitespace of fields is removed. Fields enclosed in double quotes
+/// may contain the delimiter and double quotes escaped by doubling them.
+///
+/// Returns the zero-based column of a field with unbalanced quotes as error.
+fn split_fields(line: &str, delimiter: char) -> ::std::result::Result<Vec<String>, usize> {
+	let mut fields = vec![];
+	let mut chars  = line.chars().peekable();
+	loop {
+		let column = fields.len();
+		let mut field = String::new();
+		while chars.peek().map_or(false, |&c| c != delimiter && c.is_whitespace()) {
+			chars.next();
+		}
+		if chars.peek() == Some(&'"') {
+			chars.next();
+			loop {
+				match chars.next() {
+					Some('"') if chars.peek() == Some(&'"') => { chars.next(); field.push('"') }
+					Some('"')                               => break,
+					Some(c)                                 => field.push(c),
+					None                                    => return Err(column)
+				}
+			}
+			while let Some(&c) = chars.peek() {
+				if c == delimiter {
+					break
+				}
+				if !c.is_whitespace() {
+					return Err(column)
+				}
+				chars.next();
+			}
+		}
+		else {
+			while let Some(&c) = chars.peek() {
+				if c == delimiter {
+					break
+				}
+				field.push(c);
+				chars.next();
+			}
+			field.truncate(field.trim_end().len());
+		}
+		fields.push(field);
+		if chars.next().is_none() {
+			return Ok(fields)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn values(samples: &[Sample]) -> Vec<(Vec<f32>, Vec<f32>)> {
+		samples
+			.iter()
+			.map(|sample| (sample.input.to_vec(), sample.target.to_vec()))
+			.collect()
+	}
+
+	#[test]
+	fn load_default() {
+		let samples = CsvLoader::new().load_str("1, 2, 3\n\n4, 5, 6\n").unwrap();
+		assert_eq!(values(&samples), vec![
+			(vec![1.0, 2.0], vec![3.0]),
+			(vec![4.0, 5.0], vec![6.0])
+		]);
+	}
+
+	#[test]
+	fn load_with_header_and_names() {
+		let samples = CsvLoader::new()
+			.delimiter(';')
+			.header(true)
+			.targets(Columns::Names(vec!["y".to_owned()]))
+			.inputs(Columns::Indices(vec![2, 0]))
+			.load_str("\"a\";y;c\n1;2;3\n4;5;6\n")
+			.unwrap();
+		assert_eq!(values(&samples), vec![
+			(vec![3.0, 1.0], vec![2.0]),
+			(vec![6.0, 4.0], vec![5.0])
+		]);
+	}
+
+	#[test]
+	fn quoted_fields() {
+		assert_eq!(split_fields(r#" 1 ,"2, 3", "say ""hi""" ,,"#, ','),
+			Ok(vec!["1".to_owned(), "2, 3".to_owned(), r#"say "hi""#.to_owned(), "".to_owned(), "".to_owned()]));
+		assert_eq!(split_fields("a\t\"b\tc\"", '\t'), Ok(vec!["a".to_owned(), "b\tc".to_owned()]));
+		assert_eq!(split_fields(r#"1,"2"#, ','), Err(1));
+		assert_eq!(split_fields(r#""1"x,2"#, ','), Err(0));
+		let samples = CsvLoader::new()
+			.header(true)
+			.load_str("\"x, first\",\"x, second\",y\n\"1\",2,\"3\"\n")
+			.unwrap();
+		assert_eq!(values(&samples), vec![(vec![1.0, 2.0], vec![3.0])]);
+		assert_eq!(CsvLoader::new().load_str("1,\"2,3\n").unwrap_err(), InvalidCsvValue{line: 1, column: 1});
+	}
+
+	#[test]
+	fn missing_values() {
+		let text = "1,2,3\n4,NA,6\n7,,9\n";
+		assert_eq!(CsvLoader::new().load_str(text).unwrap_err(), MissingCsvValue{line: 2, column: 1});
+		let skipped = CsvLoader::new().missing_values(MissingValues::SkipRow).load_str(text).unwrap();
+		assert_eq!(values(&skipped), vec![(vec![1.0, 2.0], vec![3.0])]);
+		let filled = CsvLoader::new().missing_values(MissingValues::Fill(0.0)).load_str(text).unwrap();
+		assert_eq!(values(&filled)[2], (vec![7.0, 0.0], vec![9.0]));
+	}
+
+	#[test]
+	fn malformed_rows() {
+		assert_eq!(CsvLoader::new().load_str("1,2\n\n3,x\n").unwrap_err(), InvalidCsvValue{line: 3, column: 1});
+		assert_eq!(CsvLoader::new().load_str("1,2\n3,4,5\n").unwrap_err(), InvalidCsvRowLength{line: 2});
+		assert_eq!(CsvLoader::new().targets(Columns::Names(vec!["y".to_owned()])).load_str("1,2\n").unwrap_err(),
+			InvalidCsvColumns);
+		assert_eq!(CsvLoader::new().targets(Columns::Last(2)).load_str("1,2\n").unwrap_err(), InvalidCsvColumns);
+		let overlapping = CsvLoader::new()
+			.inputs(Columns::Indices(vec![0, 1]))
+			.targets(Columns::Indices(vec![1]));
+		assert_eq!(overlapping.load_str("1,2\n").unwrap_err(), InvalidCsvColumns);
+	}
+}
@@ -1,14 +1,17 @@
 use ndarray::prelude::*;
 
+use loss::Loss;
+
 /// Handles deviations of predicted and target values of
 /// the neural network under training.
 /// 
-/// This is especially useful when using `MeanSquaredError`
-/// or `AvgNetError` criterions.
+/// This is especially useful when using `LatestLoss`
+/// or `RecentLoss` criterions.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct Deviation {
-	latest_mse   : f64,
-	recent_mse   : f64,
+	latest_loss  : f64,
+	recent_loss  : f64,
 	recent_factor: f64,
 }
 
@@ -19,50 +22,48 @@ impl Deviation {
 	pub fn new(recent_factor: f64) -> Self {
 		assert!(0.0 < recent_factor && recent_factor < 1.0);
 		Deviation{
-			latest_mse   : 0.0,
-			recent_mse   : 1.0,
+			latest_loss  : 0.0,
+			recent_loss  : 1.0,
 			recent_factor: recent_factor,
 		}
 	}
 
-	/// Calculates mean squared error based on the given actual and expected data.
-	fn update_mse<F>(&mut self, actual: ArrayView1<F>, expected: ArrayView1<F>)
-		where F: NdFloat
-	{
-		use std::ops::Div;
+	/// Calculates the loss averaged over all rows
+	/// of the given actual and expected data.
+	fn batch_loss(loss: Loss, actual: ArrayView2<f32>, expected: ArrayView2<f32>) -> f64 {
 		use itertools::multizip;
-		self.latest_mse = multizip((actual.iter(), expected.iter()))
-			.map(|(&actual, &expected)| {
-				let dx = expected - actual;
-				(dx * dx).to_f64().unwrap()
-			})
-			.sum::<f64>()
-			.div(actual.len() as f64)
-			.sqrt();
+		multizip((actual.genrows(), expected.genrows()))
+			.map(|(actual, expected)| loss.error(actual, expected))
+			.sum::<f64>() / actual.rows() as f64
+	}
+
+	/// Calculates recent loss based on the recent factor smoothing.
+	fn update_recent_loss(&mut self) {
+		self.recent_loss = self.recent_factor * self.recent_loss
+			+ (1.0 - self.recent_factor) * self.latest_loss;
 	}
 
-	/// Calculates recent mean squared error based on the recent factor smoothing.
-	fn update_recent_mse(&mut self) {
-		self.recent_mse = self.recent_factor * self.recent_mse
-			+ (1.0 - self.recent_factor) * self.latest_mse;
+	/// Updates the current loss including the given regularization penalty and associated data.
+	pub fn update(&mut self, loss: Loss, actual: ArrayView1<f32>, expected: ArrayView1<f32>, penalty: f64) {
+		self.latest_loss = loss.error(actual, expected) + penalty;
+		self.update_recent_loss();
 	}
 
-	/// Updates the current mean squared error and associated data.
-	pub fn update<F>(&mut self, actual: ArrayView1<F>, expected: ArrayView1<F>)
-		where F: NdFloat
-	{
-		self.update_mse(actual, expected);
-		self.update_recent_mse();
+	/// Updates the current loss including the given regularization penalty and associated data
+	/// for a batch of actual and expected data with one sample per row.
+	pub fn update_batch(&mut self, loss: Loss, actual: ArrayView2<f32>, expected: ArrayView2<f32>, penalty: f64) {
+		self.latest_loss = Self::batch_loss(loss, actual, expected) + penalty;
+		self.update_recent_loss();
 	}
 
-	/// Gets the latest mean squared error.
-	pub fn latest_mse(&self) -> f64 {
-		self.latest_mse
+	/// Gets the latest loss.
+	pub fn latest_loss(&self) -> f64 {
+		self.latest_loss
 	}
 
-	/// Gets the recent mean squared error.
-	pub fn recent_mse(&self) -> f64 {
-		self.recent_mse
+	/// Gets the recent loss.
+	pub fn recent_loss(&self) -> f64 {
+		self.recent_loss
 	}
 }
 
@@ -0,0 +1,283 @@
+//! Loads samples from files in the IDX format, e.g. the MNIST database of handwritten digits.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+use errors::ErrorKind::{SampleSourceIo, InvalidIdx, InvalidIdxLabel};
+use errors::Result;
+use mentor::samples::Sample;
+
+/// The data types of values in IDX files.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum DataType {
+	UnsignedByte,
+	SignedByte,
+	Short,
+	Int,
+	Float,
+	Double,
+}
+
+impl DataType {
+	/// Returns the data type of the given IDX type code.
+	fn from_code(code: u8) -> Result<DataType> {
+		use self::DataType::*;
+		match code {
+			0x08 => Ok(UnsignedByte),
+			0x09 => Ok(SignedByte),
+			0x0B => Ok(Short),
+			0x0C => Ok(Int),
+			0x0D => Ok(Float),
+			0x0E => Ok(Double),
+			_    => Err(InvalidIdx)
+		}
+	}
+
+	/// Returns the size of a single value in bytes.
+	fn size(self) -> usize {
+		use self::DataType::*;
+		match self {
+			UnsignedByte | SignedByte => 1,
+			Short                     => 2,
+			Int | Float               => 4,
+			Double                    => 8,
+		}
+	}
+
+	/// Decodes the given big-endian bytes of a single value.
+	fn decode(self, bytes: &[u8]) -> f64 {
+		use self::DataType::*;
+		let mut word = [0; 8];
+		word[8 - bytes.len()..].copy_from_slice(bytes);
+		let bits = u64::from_be_bytes(word);
+		match self {
+			UnsignedByte => f64::from(bits as u8),
+			SignedByte   => f64::from(bits as u8 as i8),
+			Short        => f64::from(bits as u16 as i16),
+			Int          => f64::from(bits as u32 as i32),
+			Float        => f64::from(f32::from_bits(bits as u32)),
+			Double       => f64::from_bits(bits),
+		}
+	}
+
+	/// Normalizes the given decoded value into `[0,1]` for integer types.
+	///
+	/// Floating point values are kept as they are.
+	fn normalize(self, value: f64) -> f64 {
+		use self::DataType::*;
+		match self {
+			UnsignedByte => value / f64::from(u8::MAX),
+			SignedByte   => (value - f64::from(i8::MIN)) / f64::from(u8::MAX),
+			Short        => (value - f64::from(i16::MIN)) / f64::from(u16::MAX),
+			Int          => (value - f64::from(i32::MIN)) / f64::from(u32::MAX),
+			Float        |
+			Double       => value,
+		}
+	}
+
+	/// Returns `true` if this is an integer type.
+	fn is_integer(self) -> bool {
+		!matches!(self, DataType::Float | DataType::Double)
+	}
+}
+
+/// Reads the items of an IDX file one after another.
+///
+/// Items are the entries along the first dimension, e.g. single images.
+#[derive(Debug)]
+struct IdxReader<R> {
+	reader   : R,
+	data_type: DataType,
+	len      : usize,
+	item_len : usize,
+	item_size: usize,
+	buffer   : Vec<u8>,
+}
+
+impl<R: Read> IdxReader<R> {
+	/// Reads the header of the IDX data of the given reader.
+	///
+	/// Fails if the declared size of the data overflows or
+	/// exceeds the given size in bytes of the IDX data if known.
+	fn new(mut reader: R, size: Option<u64>) -> Result<Self> {
+		let mut magic = [0; 4];
+		read_exact(&mut reader, &mut magic)?;
+		if magic[0] != 0 || magic[1] != 0 || magic[3] == 0 {
+			return Err(InvalidIdx)
+		}
+		let data_type = DataType::from_code(magic[2])?;
+		let mut dims = vec![];
+		for _ in 0..magic[3] {
+			let mut dim = [0; 4];
+			read_exact(&mut reader, &mut dim)?;
+			dims.push(u32::from_be_bytes(dim) as usize);
+		}
+		let item_len = dims[1..]
+			.iter()
+			.try_fold(1usize, |len, &dim| len.checked_mul(dim))
+			.ok_or(InvalidIdx)?;
+		let item_size = item_len.checked_mul(data_type.size()).ok_or(InvalidIdx)?;
+		let data_size = item_size.checked_mul(dims[0]).ok_or(InvalidIdx)?;
+		let header_size = 4 * (dims.len() as u64 + 1);
+		if size.map_or(false, |size| data_size as u64 > size.saturating_sub(header_size)) {
+			return Err(InvalidIdx)
+		}
+		Ok(IdxReader{
+			reader   : reader,
+			data_type: data_type,
+			len      : dims[0],
+			item_len,
+			item_size,
+			buffer   : vec![],
+		})
+	}
+
+	/// Reads the next item and returns its decoded values.
+	///
+	/// The buffer grows with the actually read data, so items declared
+	/// larger than the data do not allocate their declared size up front.
+	fn next_item(&mut self) -> Result<Vec<f64>> {
+		self.buffer.clear();
+		(&mut self.reader)
+			.take(self.item_size as u64)
+			.read_to_end(&mut self.buffer)
+			.map_err(|_| SampleSourceIo)?;
+		if self.buffer.len() < self.item_size {
+			return Err(InvalidIdx)
+		}
+		let data_type = self.data_type;
+		Ok(self.buffer
+			.chunks(data_type.size())
+			.map(|bytes| data_type.decode(bytes))
+			.collect())
+	}
+}
+
+/// Reads exactly enough bytes to fill the given buffer.
+///
+/// Truncated IDX data is invalid.
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<()> {
+	reader.read_exact(buffer).map_err(|error| {
+		if error.kind() == ::std::io::ErrorKind::UnexpectedEof { InvalidIdx } else { SampleSourceIo }
+	})
+}
+
+/// Loads samples from the given IDX image and label files, e.g. of the MNIST database.
+///
+/// See `read_idx` for details.
+pub fn load_idx<P, Q>(images: P, labels: Q, len_output: usize) -> Result<Vec<Sample>>
+	where P: AsRef<Path>,
+	      Q: AsRef<Path>
+{
+	let open = |path: &Path| -> Result<(BufReader<File>, Option<u64>)> {
+		let file = File::open(path).map_err(|_| SampleSourceIo)?;
+		let size = file.metadata().map_err(|_| SampleSourceIo)?.len();
+		Ok((BufReader::new(file), Some(size)))
+	};
+	let (images, images_size) = open(images.as_ref())?;
+	let (labels, labels_size) = open(labels.as_ref())?;
+	read_idx_sized(images, images_size, labels, labels_size, len_output)
+}
+
+/// Reads samples from the given uncompressed IDX image and label data.
+///
+/// The inputs of a sample are the values of an image in row-major order.
+/// Values of integer types are normalized into `[0,1]`, e.g. pixels
+/// of the MNIST database are divided by `255`.
+///
+/// The targets of a sample are a one-hot encoding of its label
+/// with the given length which usually is the `len_output` of the topology.
+///
+/// # Errors
+///
+/// - `ErrorKind::InvalidIdx` if the data is malformed or truncated,
+///   if the labels are no integers or if there are not as many labels as images.
+/// - `ErrorKind::InvalidIdxLabel` if a label is out of bounds for the given length.
+pub fn read_idx<R1, R2>(images: R1, labels: R2, len_output: usize) -> Result<Vec<Sample>>
+	where R1: Read,
+	      R2: Read
+{
+	read_idx_sized(images, None, labels, None, len_output)
+}
+
+/// Reads samples like `read_idx` from IDX image and label data of the given sizes if known.
+fn read_idx_sized<R1, R2>(images     : R1,
+                          images_size: Option<u64>,
+                          labels     : R2,
+                          labels_size: Option<u64>,
+                          len_output : usize)
+                          -> Result<Vec<Sample>>
+	where R1: Read,
+	      R2: Read
+{
+	let mut images = IdxReader::new(images, images_size)?;
+	let mut labels = IdxReader::new(labels, labels_size)?;
+	if images.len != labels.len || labels.item_len != 1 || !labels.data_type.is_integer() {
+		return Err(InvalidIdx)
+	}
+	(0..images.len)
+		.map(|index| {
+			let data_type = images.data_type;
+			let input = images
+				.next_item()?
+				.into_iter()
+				.map(|value| data_type.normalize(value) as f32)
+				.collect::<Vec<_>>();
+			let label = labels.next_item()?[0];
+			if label < 0.0 || label >= len_output as f64 {
+				return Err(InvalidIdxLabel{index: index})
+			}
+			let mut target = vec![0.0; len_output];
+			target[label as usize] = 1.0;
+			Ok(Sample::new(input, target))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn images() -> Vec<u8> {
+		let mut data = vec![0, 0, 0x08, 3, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 2];
+		data.extend(&[0, 255, 51, 102]);
+		data
+	}
+
+	fn labels(first: u8, second: u8) -> Vec<u8> {
+		vec![0, 0, 0x08, 1, 0, 0, 0, 2, first, second]
+	}
+
+	#[test]
+	fn read_samples() {
+		let samples = read_idx(&images()[..], &labels(2, 0)[..], 3).unwrap();
+		assert_eq!(samples.len(), 2);
+		assert_eq!(samples[0].input.to_vec(), vec![0.0, 1.0]);
+		assert_eq!(samples[0].target.to_vec(), vec![0.0, 0.0, 1.0]);
+		assert_eq!(samples[1].input.to_vec(), vec![0.2, 0.4]);
+		assert_eq!(samples[1].target.to_vec(), vec![1.0, 0.0, 0.0]);
+	}
+
+	#[test]
+	fn read_invalid() {
+		assert_eq!(read_idx(&images()[..], &labels(0, 3)[..], 3).unwrap_err(), InvalidIdxLabel{index: 1});
+		assert_eq!(read_idx(&images()[..15], &labels(0, 1)[..], 3).unwrap_err(), InvalidIdx);
+		assert_eq!(read_idx(&images()[..19], &labels(0, 1)[..], 3).unwrap_err(), InvalidIdx);
+		assert_eq!(read_idx(&images()[..], &labels(0, 1)[..9], 3).unwrap_err(), InvalidIdx);
+		assert_eq!(read_idx(&labels(0, 1)[..], &images()[..], 3).unwrap_err(), InvalidIdx);
+	}
+
+	#[test]
+	fn read_oversized() {
+		let mut huge = vec![0, 0, 0x0E, 4, 0, 0, 0, 2];
+		huge.extend(&[0xFF; 12]);
+		assert_eq!(IdxReader::new(&huge[..], None).unwrap_err(), InvalidIdx);
+		let declared = [0, 0, 0x08, 2, 0, 0, 0, 1, 0x10, 0, 0, 0];
+		let mut reader = IdxReader::new(&declared[..], None).unwrap();
+		assert_eq!(reader.next_item().unwrap_err(), InvalidIdx);
+		assert_eq!(IdxReader::new(&declared[..], Some(declared.len() as u64)).unwrap_err(), InvalidIdx);
+		let images = images();
+		assert_eq!(read_idx_sized(&images[..], Some(images.len() as u64), &labels(0, 1)[..], Some(10), 3).unwrap().len(), 2);
+	}
+}
@@ -1,71 +1,121 @@
 use std::time::{SystemTime, Duration};
 
+use neural_net::NeuralNet;
 use mentor::configs::LogConfig;
+use mentor::adapt::LearnRatePolicy;
+use mentor::observer::{TrainingObserver, Observation, Observers};
 
 /// Status during the learning process.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct Stats {
 	/// Number of samples learned so far.
 	pub iterations  : u64,
 
+	/// Number of epochs passed so far.
+	///
+	/// An epoch has passed every time as many samples have been
+	/// learned as there are training samples.
+	pub epochs      : u64,
+
 	/// Time passed since beginning of the training.
 	pub elapsed_time: Duration,
 
-	/// The latest mean squared error.
-	pub latest_mse  : f64,
+	/// The loss of the latest iteration.
+	pub latest_loss : f64,
+
+	/// The recent loss smoothed over the latest iterations.
+	pub recent_loss : f64,
+
+	/// The learn rate currently in use.
+	///
+	/// This reflects the decisions of the mentor if the
+	/// learn rate is adapted during training.
+	pub learn_rate  : f64,
+
+	/// The policy by which the learn rate is chosen,
+	/// e.g. fixed or adapted with the given factors.
+	pub learn_rate_policy: LearnRatePolicy,
+
+	/// The learn momentum currently in use.
+	///
+	/// This reflects the decisions of the mentor if the
+	/// learn momentum is adapted during training.
+	pub learn_mom   : f64,
 
-	/// The recent mean squared error.
-	pub recent_mse  : f64
+	/// The loss of the latest evaluation
+	/// on the validation samples.
+	///
+	/// This is `None` if no validation took place, yet.
+	pub validation_loss: Option<f64>
 }
 
-/// Logger facility for stats logging during the learning process.
+/// Logs the stats of the learning process via the `log` crate
+/// in intervals based on the given duration.
 #[derive(Debug, Clone)]
-pub enum Logger {
-	Never,
-	TimeSteps{
-		last_log: SystemTime,
-		interval: Duration
-	},
-	Iterations(u64)
+pub struct TimeStepsLogger {
+	last_log: SystemTime,
+	interval: Duration
 }
 
-impl From<LogConfig> for Logger {
-	fn from(config: LogConfig) -> Self {
-		use self::LogConfig::*;
-		match config {
-			Never => Logger::Never,
-			TimeSteps(duration) => Logger::TimeSteps{
-				last_log: SystemTime::now(),
-				interval: duration
-			},
-			Iterations(interval) => Logger::Iterations(interval)
+impl TimeStepsLogger {
+	/// Creates a new logger that logs every time the given duration has passed.
+	pub fn new(interval: Duration) -> Self {
+		TimeStepsLogger{
+			last_log: SystemTime::now(),
+			interval: interval
+		}
+	}
+}
+
+impl TrainingObserver for TimeStepsLogger {
+	fn observe(&mut self, stats: &Stats, _: &NeuralNet) -> Observation {
+		if self.last_log.elapsed().expect("expected valid duration") >= self.interval {
+			info!("{:?}", stats);
+			self.last_log = SystemTime::now();
 		}
+		Observation::Continue
 	}
 }
 
-impl Logger {
-	fn log(stats: Stats) {
-		info!("{:?}\n", stats);
-		println!("{:?}", stats)
+/// Logs the stats of the learning process via the `log` crate
+/// every given number of training iterations.
+#[derive(Debug, Clone)]
+pub struct IterationsLogger {
+	last_log: u64,
+	interval: u64
+}
+
+impl IterationsLogger {
+	/// Creates a new logger that logs every given number of training iterations.
+	pub fn new(interval: u64) -> Self {
+		IterationsLogger{
+			last_log: 0,
+			interval: interval
+		}
+	}
+}
+
+impl TrainingObserver for IterationsLogger {
+	fn observe(&mut self, stats: &Stats, _: &NeuralNet) -> Observation {
+		if stats.iterations - self.last_log >= self.interval {
+			info!("{:?}", stats);
+			self.last_log = stats.iterations;
+		}
+		Observation::Continue
 	}
+}
 
-	pub fn try_log(&mut self, stats: Stats) {
-		use self::Logger::*;
-		match *self {
-			TimeSteps{ref mut last_log, interval} => {
-				if last_log.elapsed().expect("expected valid duration") >= interval {
-					Self::log(stats);
-					*last_log = SystemTime::now();
-				}
-			},
-			Iterations(interval) => {
-				if stats.iterations % interval == 0 {
-					Self::log(stats)
-				}
-			},
-			_ => {
+impl LogConfig {
+	/// Adds the built-in logger of this configuration to the given observers.
+	pub fn add_logger(self, observers: &mut Observers) {
+		use self::LogConfig::*;
+		match self {
+			Never => {
 				// nothing to do here!
 			}
+			TimeSteps(interval) => observers.push(TimeStepsLogger::new(interval)),
+			Iterations(interval) => observers.push(IterationsLogger::new(interval))
 		}
 	}
 }
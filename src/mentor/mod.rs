@@ -1,10 +1,22 @@
 
 pub mod configs;
 pub mod samples;
+pub mod source;
+pub mod csv;
+pub mod idx;
 pub mod logger;
 pub mod deviation;
+pub mod adapt;
+pub mod validation;
+pub mod report;
+pub mod observer;
+pub mod checkpoint;
+pub mod split;
 pub mod training;
 
+#[allow(unused_imports)]
 pub use mentor::configs::{LogConfig, Scheduling, Criterion};
+#[allow(unused_imports)]
 pub use mentor::training::{Mentor, MentorBuilder};
+#[allow(unused_imports)]
 pub use mentor::samples::{Sample, SampleView};
@@ -0,0 +1,260 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use neural_net::NeuralNet;
+use mentor::logger::Stats;
+
+/// Decision of a `TrainingObserver` about how the training goes on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Observation {
+	/// Go on with the training.
+	Continue,
+
+	/// Stop the training as soon as possible.
+	Stop,
+}
+
+/// Observes the learning process.
+///
+/// Observers are notified after every training iteration and
+/// may request to stop the training.
+///
+/// This is implemented for closures taking the same arguments as `observe`.
+pub trait TrainingObserver {
+	/// Called after every training iteration with the current stats
+	/// and the neural network under training.
+	fn observe(&mut self, stats: &Stats, net: &NeuralNet) -> Observation;
+}
+
+impl<F> TrainingObserver for F
+	where F: FnMut(&Stats, &NeuralNet) -> Observation
+{
+	fn observe(&mut self, stats: &Stats, net: &NeuralNet) -> Observation {
+		self(stats, net)
+	}
+}
+
+/// A collection of observers that are notified together.
+///
+/// Every training session owns its observers.
+#[derive(Default)]
+pub struct Observers(Vec<Box<dyn TrainingObserver + Send>>);
+
+impl Observers {
+	/// Adds the given observer to this collection.
+	pub fn push<O>(&mut self, observer: O)
+		where O: TrainingObserver + Send + 'static
+	{
+		self.0.push(Box::new(observer))
+	}
+
+	/// Returns `true` if there are no observers in this collection.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Notifies all observers and returns `Observation::Stop`
+	/// if any of them requested to stop the training.
+	pub fn notify(&mut self, stats: &Stats, net: &NeuralNet) -> Observation {
+		self.0.iter_mut().fold(Observation::Continue, |decision, observer| {
+			match observer.observe(stats, net) {
+				Observation::Stop => Observation::Stop,
+				Observation::Continue => decision
+			}
+		})
+	}
+}
+
+impl fmt::Debug for Observers {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("Observers")
+			.field("len", &self.0.len())
+			.finish()
+	}
+}
+
+/// Formats supported by the `MetricsWriter`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MetricsFormat {
+	/// Comma separated values with a header line.
+	Csv,
+
+	/// One JSON object per line.
+	JsonLines,
+}
+
+/// Writes the stats of the learning process to the given writer
+/// every given number of training iterations.
+///
+/// Writing stops after the first I/O error which is logged
+/// via the `log` crate.
+#[derive(Debug)]
+pub struct MetricsWriter<W: Write> {
+	writer    : W,
+	format    : MetricsFormat,
+	interval  : u64,
+	next_write: u64,
+	started   : bool,
+	failed    : bool,
+}
+
+impl<W: Write> MetricsWriter<W> {
+	/// Creates a new metrics writer with the given format that
+	/// writes every given number of training iterations.
+	pub fn new(writer: W, format: MetricsFormat, interval: u64) -> Self {
+		let interval = interval.max(1);
+		MetricsWriter{
+			writer    : writer,
+			format    : format,
+			interval  : interval,
+			next_write: interval,
+			started   : false,
+			failed    : false,
+		}
+	}
+
+	/// Creates a new metrics writer for comma separated values.
+	pub fn csv(writer: W, interval: u64) -> Self {
+		Self::new(writer, MetricsFormat::Csv, interval)
+	}
+
+	/// Creates a new metrics writer for JSON lines.
+	pub fn json_lines(writer: W, interval: u64) -> Self {
+		Self::new(writer, MetricsFormat::JsonLines, interval)
+	}
+
+	/// Consumes this metrics writer and returns the underlying writer.
+	pub fn into_inner(self) -> W {
+		self.writer
+	}
+
+	fn write_csv(&mut self, stats: &Stats) -> io::Result<()> {
+		if !self.started {
+			writeln!(self.writer,
+				"iterations,epochs,elapsed_secs,latest_loss,recent_loss,learn_rate,learn_mom,validation_loss")?;
+		}
+		write!(self.writer, "{},{},{},{},{},{},{},",
+			stats.iterations,
+			stats.epochs,
+			stats.elapsed_time.as_secs_f64(),
+			stats.latest_loss,
+			stats.recent_loss,
+			stats.learn_rate,
+			stats.learn_mom)?;
+		if let Some(validation_loss) = stats.validation_loss {
+			write!(self.writer, "{}", validation_loss)?;
+		}
+		writeln!(self.writer)
+	}
+
+	fn write_json_lines(&mut self, stats: &Stats) -> io::Result<()> {
+		/// JSON has no representation for infinite or NaN numbers.
+		fn number(value: Option<f64>) -> String {
+			match value {
+				Some(value) if value.is_finite() => value.to_string(),
+				_                                => "null".to_owned()
+			}
+		}
+		writeln!(self.writer,
+			"{{\"iterations\":{},\"epochs\":{},\"elapsed_secs\":{},\"latest_loss\":{},\"recent_loss\":{},\
+			\"learn_rate\":{},\"learn_mom\":{},\"validation_loss\":{}}}",
+			stats.iterations,
+			stats.epochs,
+			stats.elapsed_time.as_secs_f64(),
+			number(Some(stats.latest_loss)),
+			number(Some(stats.recent_loss)),
+			number(Some(stats.learn_rate)),
+			number(Some(stats.learn_mom)),
+			number(stats.validation_loss))
+	}
+}
+
+impl<W: Write> TrainingObserver for MetricsWriter<W> {
+	fn observe(&mut self, stats: &Stats, _: &NeuralNet) -> Observation {
+		if self.failed || stats.iterations < self.next_write {
+			return Observation::Continue
+		}
+		while self.next_write <= stats.iterations {
+			self.next_write += self.interval;
+		}
+		let result = match self.format {
+			MetricsFormat::Csv       => self.write_csv(stats),
+			MetricsFormat::JsonLines => self.write_json_lines(stats)
+		};
+		self.started = true;
+		if let Err(error) = result {
+			error!("failed to write training metrics: {}", error);
+			self.failed = true;
+		}
+		Observation::Continue
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+	use mentor::adapt::LearnRatePolicy;
+	use topology::*;
+	use activation::Activation::Identity;
+
+	fn stats(iterations: u64) -> Stats {
+		Stats{
+			iterations     : iterations,
+			epochs         : iterations / 4,
+			elapsed_time   : Duration::from_millis(1500),
+			latest_loss    : 0.25,
+			recent_loss    : 0.5,
+			learn_rate     : 0.25,
+			learn_rate_policy: LearnRatePolicy::Fixed,
+			learn_mom      : 0.5,
+			validation_loss: None
+		}
+	}
+
+	fn net() -> NeuralNet {
+		NeuralNet::from_topology(Topology::input(1).output(1, Identity))
+	}
+
+	#[test]
+	fn write_csv() {
+		let net = net();
+		let mut writer = MetricsWriter::csv(vec![], 2);
+		for iterations in 1..6 {
+			writer.observe(&stats(iterations), &net);
+		}
+		let output = String::from_utf8(writer.into_inner()).unwrap();
+		assert_eq!(output,
+			"iterations,epochs,elapsed_secs,latest_loss,recent_loss,learn_rate,learn_mom,validation_loss\n\
+			 2,0,1.5,0.25,0.5,0.25,0.5,\n\
+			 4,1,1.5,0.25,0.5,0.25,0.5,\n");
+	}
+
+	#[test]
+	fn write_json_lines() {
+		let net = net();
+		let mut writer = MetricsWriter::json_lines(vec![], 1);
+		writer.observe(&stats(1), &net);
+		writer.observe(&Stats{validation_loss: Some(0.125), ..stats(2)}, &net);
+		let output = String::from_utf8(writer.into_inner()).unwrap();
+		assert_eq!(output,
+			"{\"iterations\":1,\"epochs\":0,\"elapsed_secs\":1.5,\"latest_loss\":0.25,\"recent_loss\":0.5,\
+			 \"learn_rate\":0.25,\"learn_mom\":0.5,\"validation_loss\":null}\n\
+			 {\"iterations\":2,\"epochs\":0,\"elapsed_secs\":1.5,\"latest_loss\":0.25,\"recent_loss\":0.5,\
+			 \"learn_rate\":0.25,\"learn_mom\":0.5,\"validation_loss\":0.125}\n");
+	}
+
+	#[test]
+	fn notify_observers() {
+		let net = net();
+		let mut observers = Observers::default();
+		assert!(observers.is_empty());
+		observers.push(|_: &Stats, _: &NeuralNet| Observation::Continue);
+		assert_eq!(observers.notify(&stats(1), &net), Observation::Continue);
+		observers.push(|stats: &Stats, _: &NeuralNet| {
+			if stats.iterations >= 10 { Observation::Stop } else { Observation::Continue }
+		});
+		assert_eq!(observers.notify(&stats(9), &net), Observation::Continue);
+		assert_eq!(observers.notify(&stats(10), &net), Observation::Stop);
+	}
+}
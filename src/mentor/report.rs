@@ -0,0 +1,115 @@
+use mentor::configs::Criterion;
+use mentor::logger::Stats;
+
+/// Reasons for a training session to stop.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopReason {
+	/// The given criterion has been met.
+	///
+	/// For composite criteria this is the sub-criterion that has
+	/// been met or the whole `All` criterion if all of them are met.
+	Criterion(Criterion),
+
+	/// An observer requested to stop the training.
+	Observer,
+}
+
+/// Summary of a finished training session.
+///
+/// Returned together with the trained neural network by `Mentor::go`.
+#[derive(Debug, Clone)]
+pub struct TrainingReport {
+	/// The stats at the end of the training.
+	///
+	/// These reflect the last state of the training even if the best
+	/// neural network according to the validation samples is returned.
+	pub stats: Stats,
+
+	/// The reason why the training has stopped.
+	pub stop_reason: StopReason,
+
+	/// Stats sampled in regular intervals of iterations during the training.
+	///
+	/// This is empty unless a history interval has been set.
+	pub history: Vec<Stats>,
+}
+
+/// Records stats in regular intervals of iterations.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct History {
+	interval   : u64,
+	next_record: u64,
+	records    : Vec<Stats>,
+}
+
+impl History {
+	/// Creates a new history recording stats every time
+	/// the given amount of iterations has passed.
+	///
+	/// Returns `None` if the interval is `None`.
+	pub fn new(interval: Option<u64>) -> Option<Self> {
+		interval.map(|interval| History{
+			interval   : interval,
+			next_record: interval,
+			records    : vec![],
+		})
+	}
+
+	/// Returns `true` if the given iteration has reached the next record.
+	pub fn is_due(&self, iterations: u64) -> bool {
+		iterations >= self.next_record
+	}
+
+	/// Records the given stats.
+	pub fn record(&mut self, stats: Stats) {
+		while self.next_record <= stats.iterations {
+			self.next_record += self.interval;
+		}
+		self.records.push(stats)
+	}
+
+	/// Consumes this history and returns all recorded stats.
+	pub fn into_records(self) -> Vec<Stats> {
+		self.records
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::time::Duration;
+	use mentor::adapt::LearnRatePolicy;
+
+	fn stats(iterations: u64) -> Stats {
+		Stats{
+			iterations     : iterations,
+			epochs         : iterations / 4,
+			elapsed_time   : Duration::from_secs(0),
+			latest_loss    : 0.0,
+			recent_loss    : 0.0,
+			learn_rate     : 0.3,
+			learn_rate_policy: LearnRatePolicy::Fixed,
+			learn_mom      : 0.5,
+			validation_loss: None
+		}
+	}
+
+	#[test]
+	fn records_in_intervals() {
+		let mut history = History::new(Some(10)).unwrap();
+		assert!(!history.is_due(9));
+		assert!(history.is_due(10));
+		history.record(stats(10));
+		assert!(!history.is_due(19));
+		assert!(history.is_due(24));
+		history.record(stats(24));
+		assert!(!history.is_due(29));
+		assert!(history.is_due(30));
+		let iterations = history.into_records()
+			.iter()
+			.map(|stats| stats.iterations)
+			.collect::<Vec<_>>();
+		assert_eq!(iterations, vec![10, 24]);
+	}
+}
@@ -1,8 +1,16 @@
+use std::collections::BTreeMap;
+
 use ndarray::prelude::*;
 
-use rand::{Rng, ThreadRng, thread_rng};
+use rand::Rng;
+
+use rng::Prng;
 
+use errors::ErrorKind::{EmptySampleSource, InvalidSampleInputSize, InvalidSampleTargetSize};
+use errors::Result;
 use mentor::configs::Scheduling;
+use mentor::source::BoxedSource;
+use scaling::SampleScaling;
 
 // /// Mentors are objects that train a given disciple structure
 // /// resulting in a prophet structure that can be used to predict
@@ -28,6 +36,11 @@ pub struct Sample {
 
 	/// The expected target values of this `Sample`.
 	pub target: Array1<f32>,
+
+	/// The weight of this `Sample` for weighted scheduling.
+	///
+	/// Defaults to `1.0`.
+	pub weight: f32,
 }
 
 impl Sample {
@@ -38,9 +51,23 @@ impl Sample {
 	{
 		Sample{
 			input : Array1::from_vec(input.into()),
-			target: Array1::from_vec(target.into())
+			target: Array1::from_vec(target.into()),
+			weight: 1.0
 		}
 	}
+
+	/// Sets the weight of this sample for weighted scheduling.
+	///
+	/// The weight must be finite and non-negative.
+	pub fn with_weight(mut self, weight: f32) -> Sample {
+		self.weight = weight;
+		self
+	}
+
+	/// Returns `true` if the weight of this sample is valid.
+	pub fn has_valid_weight(&self) -> bool {
+		self.weight.is_finite() && self.weight >= 0.0
+	}
 }
 
 impl<A1, A2> From<(A1, A2)> for Sample
@@ -165,29 +192,68 @@ macro_rules! samples {
 #[derive(Clone)]
 enum Scheduler {
 	/// Samples randomly.
-	Random(ThreadRng),
+	Random(Prng),
 
 	/// Samples iteratively.
 	Iterative(u64),
+
+	/// Samples in a random order that is reshuffled every epoch.
+	///
+	/// Holds the order of the current epoch and the position within it.
+	Shuffled(Prng, Vec<usize>, usize),
+
+	/// Samples randomly with probabilities proportional to the sample weights.
+	///
+	/// Holds the cumulative sample weights.
+	Weighted(Prng, Vec<f64>),
+
+	/// Samples randomly from uniformly picked classes.
+	///
+	/// Holds the sample indices of every class.
+	Stratified(Prng, Vec<Vec<usize>>),
 }
 
 impl ::std::fmt::Debug for Scheduler {
 	fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
 		use self::Scheduler::*;
 		match *self {
-			Random(_)    => write!(f, "Scheduler::Random(_)"),
-			Iterative(x) => write!(f, "Scheduler::Iterative({})", x),
+			Random(_)          => write!(f, "Scheduler::Random(_)"),
+			Iterative(x)       => write!(f, "Scheduler::Iterative({})", x),
+			Shuffled(_, _, x)  => write!(f, "Scheduler::Shuffled(_, _, {})", x),
+			Weighted(_, _)     => write!(f, "Scheduler::Weighted(_, _)"),
+			Stratified(_, ref classes) => {
+				write!(f, "Scheduler::Stratified(_, {} classes)", classes.len())
+			}
 		}
 	}
 }
 
 impl Scheduler {
-	/// Creates a new `Scheduler` from a given scheduling strategy.
-	fn from_kind(kind: Scheduling) -> Self {
+	/// Creates a new `Scheduler` for the given samples from a given scheduling
+	/// strategy drawing from the given random number generator if required.
+	fn from_kind(kind: Scheduling, rng: Prng, samples: &[Sample]) -> Self {
 		use mentor::configs::Scheduling::*;
 		match kind {
-			Random    => Scheduler::Random(thread_rng()),
+			Random    => Scheduler::Random(rng),
 			Iterative => Scheduler::Iterative(0),
+			Shuffled  => Scheduler::Shuffled(rng, vec![], 0),
+			Weighted  => {
+				let cumulative = samples
+					.iter()
+					.scan(0.0, |total, sample| {
+						*total += f64::from(sample.weight);
+						Some(*total)
+					})
+					.collect();
+				Scheduler::Weighted(rng, cumulative)
+			}
+			Stratified(label) => {
+				let mut classes = BTreeMap::new();
+				for (id, sample) in samples.iter().enumerate() {
+					classes.entry(label(sample)).or_insert_with(Vec::new).push(id);
+				}
+				Scheduler::Stratified(rng, classes.into_values().collect())
+			}
 		}
 	}
 
@@ -206,31 +272,197 @@ impl Scheduler {
 				*cur += 1;
 				next
 			}
+			Weighted(ref mut rng, ref cumulative) => {
+				let total = *cumulative.last().expect("there must be samples to schedule!");
+				let point = rng.gen_range(0.0, total);
+				// The cumulative weights are sorted, so the first bound
+				// above the point is found with a binary search.
+				cumulative
+					.partition_point(|&bound| bound <= point)
+					.min(num_samples - 1)
+			}
+			Stratified(ref mut rng, ref classes) => {
+				let class = &classes[rng.gen_range(0, classes.len())];
+				class[rng.gen_range(0, class.len())]
+			}
+			Shuffled(ref mut rng, ref mut order, ref mut cur) => {
+				if *cur >= order.len() {
+					*order = (0..num_samples).collect();
+					rng.shuffle(order);
+					*cur = 0;
+				}
+				let next = order[*cur];
+				*cur += 1;
+				next
+			}
+		}
+	}
+}
+
+/// The state of a `SampleScheduler` that is part of checkpoints
+/// so that resumed training sessions continue the sample scheduling.
+///
+/// Holds the state of the random number generator of randomized scheduling
+/// strategies and the current order and position of shuffled scheduling.
+/// Data derived from the samples, e.g. the classes of stratified scheduling,
+/// is computed anew when resuming.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum SchedulerState {
+	/// State of random scheduling.
+	Random(Prng),
+
+	/// State of iterative scheduling with the number of samples scheduled so far.
+	Iterative(u64),
+
+	/// State of shuffled scheduling with the order of the current epoch and the position within it.
+	Shuffled(Prng, Vec<usize>, usize),
+
+	/// State of weighted scheduling.
+	Weighted(Prng),
+
+	/// State of stratified scheduling.
+	Stratified(Prng),
+}
+
+/// Samples pulled one after another from a sample source.
+#[derive(Debug)]
+struct Stream {
+	source    : BoxedSource,
+	current   : Sample,
+	skip      : u64,
+	len_input : usize,
+	len_output: usize,
+	scaling   : SampleScaling,
+}
+
+impl Stream {
+	/// Pulls the next sample from the source and checks its sizes.
+	fn next_sample(&mut self) -> Result<SampleView> {
+		for _ in 0..self.skip {
+			self.source.next_sample()?;
+		}
+		self.skip = 0;
+		let sample = self.source.next_sample()?.ok_or(EmptySampleSource)?;
+		if sample.input.len() != self.len_input {
+			return Err(InvalidSampleInputSize)
 		}
+		if sample.target.len() != self.len_output {
+			return Err(InvalidSampleTargetSize)
+		}
+		self.current = sample;
+		self.scaling.apply(&mut self.current);
+		Ok((&self.current).into())
 	}
 }
 
 /// Organizes the scheduling of samples with different strategies.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct SampleScheduler {
 	samples  : Vec<Sample>,
 	scheduler: Scheduler,
+	stream   : Option<Stream>,
 }
 
 impl SampleScheduler {
 	/// Creates a new `SampleScheduler` from given samples and a scheduling strategy.
-	pub fn from_samples(kind: Scheduling, samples: Vec<Sample>) -> Self {
+	///
+	/// Random scheduling draws from the given random number generator.
+	pub fn from_samples(kind: Scheduling, samples: Vec<Sample>, rng: Prng) -> Self {
 		SampleScheduler {
+			scheduler: Scheduler::from_kind(kind, rng, &samples),
 			samples: samples,
-			scheduler: Scheduler::from_kind(kind),
+			stream: None,
+		}
+	}
+
+	/// Creates a new `SampleScheduler` that pulls samples in order from the given source.
+	///
+	/// Pulled samples are checked against the given input and target sizes
+	/// and then scaled with the given scaling.
+	pub fn from_source(source: BoxedSource, len_input: usize, len_output: usize, scaling: SampleScaling) -> Self {
+		SampleScheduler {
+			samples: vec![],
+			scheduler: Scheduler::Iterative(0),
+			stream: Some(Stream{
+				source    : source,
+				current   : Sample::new(vec![], vec![]),
+				skip      : 0,
+				len_input : len_input,
+				len_output: len_output,
+				scaling   : scaling,
+			}),
+		}
+	}
+
+	/// Returns the current state of the scheduling.
+	pub fn state(&self) -> SchedulerState {
+		match self.scheduler {
+			Scheduler::Random(ref rng)                   => SchedulerState::Random(rng.clone()),
+			Scheduler::Iterative(cur)                    => SchedulerState::Iterative(cur),
+			Scheduler::Shuffled(ref rng, ref order, cur) => SchedulerState::Shuffled(rng.clone(), order.clone(), cur),
+			Scheduler::Weighted(ref rng, _)              => SchedulerState::Weighted(rng.clone()),
+			Scheduler::Stratified(ref rng, _)            => SchedulerState::Stratified(rng.clone())
+		}
+	}
+
+	/// Continues the scheduling from the given state.
+	///
+	/// States of other scheduling strategies are ignored and shuffled
+	/// orders of a different number of samples are shuffled anew.
+	/// Sample sources skip the samples of the current pass
+	/// up to the position when the next sample is pulled.
+	pub fn set_state(&mut self, state: SchedulerState) {
+		let len = self.len();
+		match (&mut self.scheduler, state) {
+			(&mut Scheduler::Random(ref mut rng), SchedulerState::Random(saved))            |
+			(&mut Scheduler::Weighted(ref mut rng, _), SchedulerState::Weighted(saved))     |
+			(&mut Scheduler::Stratified(ref mut rng, _), SchedulerState::Stratified(saved)) => {
+				*rng = saved
+			}
+			(&mut Scheduler::Shuffled(ref mut rng, ref mut order, ref mut cur),
+			 SchedulerState::Shuffled(saved_rng, saved_order, saved_cur)) => {
+				*rng = saved_rng;
+				if saved_order.len() == len {
+					*order = saved_order;
+					*cur   = saved_cur;
+				}
+			}
+			(&mut Scheduler::Iterative(ref mut cur), SchedulerState::Iterative(position)) => {
+				*cur = position;
+				if let Some(ref mut stream) = self.stream {
+					if len > 0 {
+						stream.skip = position % len as u64;
+					}
+				}
+			}
+			_ => ()
 		}
 	}
 
+	/// Returns the number of samples.
+	pub fn len(&self) -> usize {
+		match self.stream {
+			Some(ref stream) => stream.source.len(),
+			None             => self.samples.len()
+		}
+	}
+
+	/// Returns `true` if samples are pulled from a sample source.
+	pub fn is_streamed(&self) -> bool {
+		self.stream.is_some()
+	}
+
 	/// Returns the next sample.
-	pub fn next_sample(&mut self) -> SampleView {
-		let len_samples = self.samples.len();
+	///
+	/// Fails if a sample source cannot provide a valid sample.
+	pub fn next_sample(&mut self) -> Result<SampleView> {
+		let len_samples = self.len();
 		let id = self.scheduler.next(len_samples);
-		(&self.samples[id]).into()
+		match self.stream {
+			Some(ref mut stream) => stream.next_sample(),
+			None                 => Ok((&self.samples[id]).into())
+		}
 	}
 }
 
@@ -306,4 +538,61 @@ mod tests {
 		];
 		assert_samples_eq(&s1, &s2);
 	}
+
+	#[test]
+	fn shuffled_epochs() {
+		use rng;
+		let mut scheduler = Scheduler::from_kind(Scheduling::Shuffled, rng::from_seed(42), &[]);
+		let epochs = (0..3)
+			.map(|_| (0..5).map(|_| scheduler.next(5)).collect::<Vec<_>>())
+			.collect::<Vec<_>>();
+		for epoch in &epochs {
+			let mut sorted = epoch.clone();
+			sorted.sort();
+			assert_eq!(sorted, vec![0, 1, 2, 3, 4]);
+		}
+		assert!(epochs[0] != epochs[1] || epochs[1] != epochs[2]);
+	}
+
+	#[test]
+	fn weighted() {
+		use rng;
+		let samples = vec![
+			Sample::new(vec![0.0], vec![0.0]).with_weight(1.0),
+			Sample::new(vec![1.0], vec![1.0]).with_weight(0.0),
+			Sample::new(vec![2.0], vec![2.0]).with_weight(3.0),
+		];
+		let mut scheduler = Scheduler::from_kind(Scheduling::Weighted, rng::from_seed(42), &samples);
+		let mut counts = [0; 3];
+		for _ in 0..4000 {
+			counts[scheduler.next(3)] += 1;
+		}
+		assert_eq!(counts[1], 0);
+		assert!(counts[2] > 2 * counts[0]);
+	}
+
+	#[test]
+	fn stratified() {
+		use rng;
+		fn label(sample: &Sample) -> usize {
+			sample.target[0] as usize
+		}
+		let mut samples = vec![Sample::new(vec![0.0], vec![0.0]); 9];
+		samples.push(Sample::new(vec![1.0], vec![1.0]));
+		let mut scheduler = Scheduler::from_kind(Scheduling::Stratified(label), rng::from_seed(42), &samples);
+		let minority = (0..4000).filter(|_| scheduler.next(10) == 9).count();
+		assert!(minority > 1600 && minority < 2400);
+	}
+
+	#[test]
+	fn stratified_sparse_labels() {
+		use rng;
+		fn label(sample: &Sample) -> usize {
+			if sample.target[0] > 0.0 { usize::MAX } else { 0 }
+		}
+		let samples = vec![Sample::new(vec![0.0], vec![0.0]), Sample::new(vec![1.0], vec![1.0])];
+		let mut scheduler = Scheduler::from_kind(Scheduling::Stratified(label), rng::from_seed(42), &samples);
+		assert_eq!(format!("{:?}", scheduler), "Scheduler::Stratified(_, 2 classes)");
+		assert!((0..100).all(|_| scheduler.next(2) < 2));
+	}
 }
@@ -0,0 +1,257 @@
+//! Sample sources provide training samples one after another so that
+//! samples do not have to be kept in memory all at once.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+use errors::ErrorKind::{SampleSourceIo, InvalidSampleLine};
+use errors::Result;
+use mentor::samples::Sample;
+
+/// Provides training samples one after another in passes over all of them.
+///
+/// Mentors pull samples from sources in order and reset them
+/// whenever a pass over all samples (an epoch) is done.
+pub trait SampleSource {
+	/// Returns the number of samples per pass.
+	fn len(&self) -> usize;
+
+	/// Returns `true` if this source provides no samples.
+	fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Returns the next sample of the current pass or `None` if the pass is done.
+	fn next_sample(&mut self) -> Result<Option<Sample>>;
+
+	/// Restarts this source at its first sample for the next pass.
+	fn reset(&mut self) -> Result<()>;
+}
+
+/// A sample source of samples that are kept in memory.
+#[derive(Debug, Clone)]
+pub struct MemorySource {
+	samples: Vec<Sample>,
+	next   : usize,
+}
+
+impl MemorySource {
+	/// Creates a new sample source providing the given samples in order.
+	pub fn new(samples: Vec<Sample>) -> Self {
+		MemorySource{
+			samples: samples,
+			next   : 0,
+		}
+	}
+
+	/// Consumes this source and returns its samples.
+	pub fn into_samples(self) -> Vec<Sample> {
+		self.samples
+	}
+}
+
+impl From<Vec<Sample>> for MemorySource {
+	fn from(samples: Vec<Sample>) -> Self {
+		MemorySource::new(samples)
+	}
+}
+
+impl SampleSource for MemorySource {
+	fn len(&self) -> usize {
+		self.samples.len()
+	}
+
+	fn next_sample(&mut self) -> Result<Option<Sample>> {
+		let sample = self.samples.get(self.next).cloned();
+		if sample.is_some() {
+			self.next += 1;
+		}
+		Ok(sample)
+	}
+
+	fn reset(&mut self) -> Result<()> {
+		self.next = 0;
+		Ok(())
+	}
+}
+
+/// A sample source that streams samples from a text file with one sample per line.
+///
+/// Lines are parsed into samples by the given function
+/// that returns `None` for lines that are no valid samples.
+/// Empty lines are skipped.
+///
+/// Only the current line is kept in memory.
+#[derive(Debug)]
+pub struct FileSource<F> {
+	reader : BufReader<File>,
+	parse  : F,
+	line   : String,
+	line_nr: u64,
+	len    : usize,
+}
+
+impl<F> FileSource<F>
+	where F: FnMut(&str) -> Option<Sample>
+{
+	/// Opens the file at the given path as sample source
+	/// with the given function to parse its lines into samples.
+	///
+	/// Counts the samples of the file in a first pass.
+	pub fn open<P: AsRef<Path>>(path: P, parse: F) -> Result<Self> {
+		let file = File::open(path).map_err(|_| SampleSourceIo)?;
+		let mut source = FileSource{
+			reader : BufReader::new(file),
+			parse : parse,
+			line   : String::new(),
+			line_nr: 0,
+			len    : 0,
+		};
+		while source.next_line()? {
+			source.len += 1;
+		}
+		source.reset()?;
+		Ok(source)
+	}
+
+	/// Reads the next non-empty line and returns `false` at the end of the file.
+	fn next_line(&mut self) -> Result<bool> {
+		loop {
+			self.line.clear();
+			let read = self.reader.read_line(&mut self.line).map_err(|_| SampleSourceIo)?;
+			if read == 0 {
+				return Ok(false)
+			}
+			self.line_nr += 1;
+			if !self.line.trim().is_empty() {
+				return Ok(true)
+			}
+		}
+	}
+}
+
+impl<F> SampleSource for FileSource<F>
+	where F: FnMut(&str) -> Option<Sample>
+{
+	fn len(&self) -> usize {
+		self.len
+	}
+
+	fn next_sample(&mut self) -> Result<Option<Sample>> {
+		if !self.next_line()? {
+			return Ok(None)
+		}
+		match (self.parse)(self.line.trim()) {
+			Some(sample) => Ok(Some(sample)),
+			None         => Err(InvalidSampleLine{line: self.line_nr})
+		}
+	}
+
+	fn reset(&mut self) -> Result<()> {
+		self.reader.seek(SeekFrom::Start(0)).map_err(|_| SampleSourceIo)?;
+		self.line_nr = 0;
+		Ok(())
+	}
+}
+
+/// A sample source owned by a mentor and then by its training session.
+pub struct BoxedSource(Box<dyn SampleSource + Send>);
+
+impl BoxedSource {
+	/// Boxes the given sample source.
+	pub fn new<S>(source: S) -> Self
+		where S: SampleSource + Send + 'static
+	{
+		BoxedSource(Box::new(source))
+	}
+
+	/// Returns the number of samples per pass of the boxed source.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Returns `true` if the boxed source provides no samples.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Returns the next sample of the boxed source and
+	/// starts a new pass whenever the current one is done.
+	///
+	/// Returns `None` if the source provides no samples at all.
+	pub fn next_sample(&mut self) -> Result<Option<Sample>> {
+		match self.0.next_sample()? {
+			Some(sample) => Ok(Some(sample)),
+			None => {
+				self.0.reset()?;
+				self.0.next_sample()
+			}
+		}
+	}
+}
+
+impl fmt::Debug for BoxedSource {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_struct("BoxedSource")
+			.field("len", &self.len())
+			.finish()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::env;
+	use std::fs;
+	use std::process;
+
+	fn parse(line: &str) -> Option<Sample> {
+		let values = line
+			.split(',')
+			.map(|value| value.trim().parse::<f32>().ok())
+			.collect::<Option<Vec<_>>>()?;
+		Some(Sample::new(&values[..1], &values[1..]))
+	}
+
+	#[test]
+	fn memory_source() {
+		let mut boxed = BoxedSource::new(MemorySource::new(vec![
+			Sample::new(vec![0.0], vec![1.0]),
+			Sample::new(vec![1.0], vec![0.0])
+		]));
+		assert_eq!(boxed.len(), 2);
+		let inputs = (0..5)
+			.map(|_| boxed.next_sample().unwrap().unwrap().input[0])
+			.collect::<Vec<_>>();
+		assert_eq!(inputs, vec![0.0, 1.0, 0.0, 1.0, 0.0]);
+	}
+
+	#[test]
+	fn file_source() {
+		let path = env::temp_dir().join(format!("prophet-file-source-{}", process::id()));
+		fs::write(&path, "0.0, 1.0\n\n1.0, 0.0\n0.5, 0.5\n").unwrap();
+		let mut source = FileSource::open(&path, parse).unwrap();
+		fs::remove_file(&path).unwrap();
+		assert_eq!(source.len(), 3);
+		for _ in 0..2 {
+			let inputs = (0..3)
+				.map(|_| source.next_sample().unwrap().unwrap().input[0])
+				.collect::<Vec<_>>();
+			assert_eq!(inputs, vec![0.0, 1.0, 0.5]);
+			assert!(source.next_sample().unwrap().is_none());
+			source.reset().unwrap();
+		}
+	}
+
+	#[test]
+	fn file_source_invalid_line() {
+		let path = env::temp_dir().join(format!("prophet-file-source-invalid-{}", process::id()));
+		fs::write(&path, "0.0, 1.0\n\n1.0, zero\n").unwrap();
+		let mut source = FileSource::open(&path, parse).unwrap();
+		fs::remove_file(&path).unwrap();
+		assert!(source.next_sample().unwrap().is_some());
+		assert_eq!(source.next_sample().unwrap_err(), InvalidSampleLine{line: 3});
+	}
+}
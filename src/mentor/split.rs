@@ -0,0 +1,207 @@
+//! Splits samples into training, validation and test samples
+//! and summarizes cross validation runs.
+
+use rand::Rng;
+
+use errors::ErrorKind::{InvalidSplit, InvalidFolds};
+use errors::Result;
+use mentor::samples::Sample;
+use mentor::report::TrainingReport;
+use rng;
+
+/// Samples randomly split into training, validation and test samples.
+#[derive(Debug, Clone)]
+pub struct Split {
+	/// The samples to train with.
+	pub train: Vec<Sample>,
+
+	/// The samples to validate with during the training, e.g. for early stopping.
+	pub validation: Vec<Sample>,
+
+	/// The samples to evaluate the trained neural network with.
+	pub test: Vec<Sample>,
+}
+
+impl Split {
+	/// Shuffles the given samples with the given seed and splits off the given
+	/// fractions of them as validation and test samples.
+	///
+	/// The remaining samples are the training samples.
+	/// The same samples and seed always result in the same split.
+	///
+	/// # Errors
+	///
+	/// `ErrorKind::InvalidSplit` if a fraction is not within `[0,1)`
+	/// or if no training samples would remain.
+	pub fn new(mut samples: Vec<Sample>, validation: f64, test: f64, seed: u64) -> Result<Split> {
+		let valid_fraction = |fraction: f64| (0.0..1.0).contains(&fraction);
+		if !valid_fraction(validation) || !valid_fraction(test) {
+			return Err(InvalidSplit)
+		}
+		let len      = samples.len();
+		let len_test = (len as f64 * test).round() as usize;
+		let len_val  = (len as f64 * validation).round() as usize;
+		if len_test + len_val >= len {
+			return Err(InvalidSplit)
+		}
+		rng::from_seed(seed).shuffle(&mut samples);
+		let test       = samples.split_off(len - len_test);
+		let validation = samples.split_off(len - len_test - len_val);
+		Ok(Split{
+			train     : samples,
+			validation: validation,
+			test      : test,
+		})
+	}
+}
+
+/// Shuffles the given samples with the given seed and splits them
+/// into the given number of folds of nearly equal sizes.
+///
+/// Returns the training and test samples of every fold.
+/// Every sample is a test sample of exactly one fold and
+/// a training sample of all others.
+///
+/// # Errors
+///
+/// `ErrorKind::InvalidFolds` if there are less than two folds
+/// or more folds than samples.
+pub fn k_folds(samples: &[Sample], folds: usize, seed: u64) -> Result<Vec<(Vec<Sample>, Vec<Sample>)>> {
+	Ok(Folds::new(samples, folds, seed)?.collect())
+}
+
+/// Iterates over the training and test samples of every fold
+/// of a k-fold split.
+///
+/// The samples of a fold are only copied once the fold is reached,
+/// so that only the samples of a single fold are kept at a time.
+#[derive(Debug, Clone)]
+pub(crate) struct Folds<'a> {
+	samples: &'a [Sample],
+	indices: Vec<usize>,
+	folds  : usize,
+	next   : usize,
+}
+
+impl<'a> Folds<'a> {
+	/// Shuffles the given samples with the given seed
+	/// for a split into the given number of folds.
+	///
+	/// # Errors
+	///
+	/// `ErrorKind::InvalidFolds` if there are less than two folds
+	/// or more folds than samples.
+	pub(crate) fn new(samples: &'a [Sample], folds: usize, seed: u64) -> Result<Self> {
+		if folds < 2 || folds > samples.len() {
+			return Err(InvalidFolds)
+		}
+		let mut indices = (0..samples.len()).collect::<Vec<_>>();
+		rng::from_seed(seed).shuffle(&mut indices);
+		Ok(Folds{
+			samples: samples,
+			indices: indices,
+			folds  : folds,
+			next   : 0,
+		})
+	}
+}
+
+impl<'a> Iterator for Folds<'a> {
+	type Item = (Vec<Sample>, Vec<Sample>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.next == self.folds {
+			return None
+		}
+		let fold = self.next;
+		self.next += 1;
+		let fold_of = |position: usize| position * self.folds / self.samples.len();
+		let (mut train, mut test) = (vec![], vec![]);
+		for (position, &index) in self.indices.iter().enumerate() {
+			if fold_of(position) == fold {
+				test.push(self.samples[index].clone())
+			}
+			else {
+				train.push(self.samples[index].clone())
+			}
+		}
+		Some((train, test))
+	}
+}
+
+/// The result of training and testing a neural network for a single fold.
+#[derive(Debug, Clone)]
+pub struct FoldReport {
+	/// The report of the training on the training samples of the fold.
+	pub report: TrainingReport,
+
+	/// The loss of the trained neural network
+	/// on the test samples of the fold.
+	pub test_loss: f64,
+}
+
+/// Summary of a k-fold cross validation.
+///
+/// Returned by `Mentor::cross_validate`.
+#[derive(Debug, Clone)]
+pub struct CrossValidation {
+	/// The results of every fold.
+	pub folds: Vec<FoldReport>,
+
+	/// The mean of the test errors of all folds.
+	pub mean_loss: f64,
+
+	/// The standard deviation of the test errors of all folds.
+	pub std_dev_loss: f64,
+}
+
+impl CrossValidation {
+	/// Creates a new summary of the given fold results.
+	pub(crate) fn new(folds: Vec<FoldReport>) -> Self {
+		let len      = folds.len() as f64;
+		let mean     = folds.iter().map(|fold| fold.test_loss).sum::<f64>() / len;
+		let variance = folds.iter().map(|fold| (fold.test_loss - mean).powi(2)).sum::<f64>() / len;
+		CrossValidation{
+			folds       : folds,
+			mean_loss   : mean,
+			std_dev_loss: variance.sqrt(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn samples(len: usize) -> Vec<Sample> {
+		(0..len).map(|i| Sample::new(vec![i as f32], vec![0.0])).collect()
+	}
+
+	fn inputs(samples: &[Sample]) -> Vec<f32> {
+		samples.iter().map(|sample| sample.input[0]).collect()
+	}
+
+	#[test]
+	fn split() {
+		let split = Split::new(samples(10), 0.2, 0.3, 42).unwrap();
+		assert_eq!((split.train.len(), split.validation.len(), split.test.len()), (5, 2, 3));
+		let mut all = [inputs(&split.train), inputs(&split.validation), inputs(&split.test)].concat();
+		all.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		assert_eq!(all, inputs(&samples(10)));
+		assert_eq!(inputs(&Split::new(samples(10), 0.2, 0.3, 42).unwrap().test), inputs(&split.test));
+		assert_eq!(Split::new(samples(10), 0.5, 0.5, 42).unwrap_err(), InvalidSplit);
+		assert_eq!(Split::new(samples(10), -0.1, 0.5, 42).unwrap_err(), InvalidSplit);
+	}
+
+	#[test]
+	fn folds() {
+		let folds = k_folds(&samples(10), 3, 7).unwrap();
+		assert_eq!(folds.iter().map(|fold| fold.1.len()).collect::<Vec<_>>(), vec![4, 3, 3]);
+		let mut tested = folds.iter().flat_map(|fold| inputs(&fold.1)).collect::<Vec<_>>();
+		tested.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		assert_eq!(tested, inputs(&samples(10)));
+		assert!(folds.iter().all(|fold| fold.0.len() + fold.1.len() == 10));
+		assert_eq!(k_folds(&samples(10), 1, 7).unwrap_err(), InvalidFolds);
+		assert_eq!(k_folds(&samples(2), 3, 7).unwrap_err(), InvalidFolds);
+	}
+}
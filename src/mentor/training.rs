@@ -1,14 +1,40 @@
+// The type states of the mentor builder are spelled out in every setter.
+#![allow(clippy::type_complexity)]
+
 use std::time::{SystemTime};
+#[cfg(feature = "serde_support")]
+use std::path::PathBuf;
+
+use rand::Rng;
+use ndarray::prelude::*;
 
 use neural_net::NeuralNet;
+use optimizer::Optimizer;
 use traits::{
 	LearnRate,
 	LearnMomentum,
-	Predict,
+	PredictBatch,
 	UpdateGradients,
-	UpdateWeights
+	UpdateGradientsBatch,
+	UpdateWeights,
+	UpdateWeightsBatch,
+	SetOptimizer
+};
+use errors::ErrorKind::{
+	InvalidSampleInputSize,
+	InvalidSampleTargetSize,
+	InvalidSampleWeight,
+	InvalidBatchSize,
+	EmptySampleSource,
+	InvalidScheduling,
+	InvalidHistoryInterval,
+	InvalidRegularization,
+	InvalidRollbackInterval,
+	MissingValidationSamples,
+	TrainingDiverged
 };
-use errors::ErrorKind::{InvalidSampleInputSize, InvalidSampleTargetSize};
+#[cfg(feature = "serde_support")]
+use errors::ErrorKind::InvalidCheckpointInterval;
 use errors::Result;
 use topology::Topology;
 use mentor::configs::{
@@ -16,29 +42,188 @@ use mentor::configs::{
 	LearnMomentumConfig,
 	Criterion,
 	LogConfig,
-	Scheduling
+	Scheduling,
+	BatchSize
 };
 use mentor::samples::{SampleScheduler};
+use mentor::source::{SampleSource, BoxedSource};
 use mentor::deviation::Deviation;
-use mentor::logger::{Stats, Logger};
+use mentor::logger::Stats;
+use mentor::observer::{TrainingObserver, Observation, Observers};
+use mentor::adapt::{LearnRateAdapter, LearnMomentumAdapter};
+use mentor::validation::Validator;
+use mentor::report::{TrainingReport, StopReason, History};
+use mentor::checkpoint::{Checkpoint, Progress, Rollback};
+use rng::{self, Prng};
+use scaling::{Scaler, ScalingFit, SampleScaling};
+use activation::OutputActivation;
+use loss::Loss;
+use regularization::Regularization;
+use clipping::GradientClipping;
+#[cfg(feature = "serde_support")]
+use mentor::checkpoint::Checkpointer;
 use mentor::samples::Sample;
+use mentor::split::{Folds, CrossValidation, FoldReport};
 
 
 impl Topology {
 	/// Iterates over the layer sizes of this Disciple's topology definition.
 	pub fn train(self, samples: Vec<Sample>) -> MentorBuilder {
-		Mentor::new(self, samples)
+		Mentor::new(Disciple::Untrained(self), samples, None)
+	}
+
+	/// Trains a new neural network of this topology with samples
+	/// pulled in order from the given sample source.
+	///
+	/// Samples from sources are always scheduled iteratively.
+	pub fn train_from_source<S>(self, source: S) -> MentorBuilder
+		where S: SampleSource + Send + 'static
+	{
+		Mentor::new(Disciple::Untrained(self), vec![], Some(BoxedSource::new(source)))
+	}
+}
+
+impl NeuralNet {
+	/// Resumes the training of this neural network with the given samples.
+	///
+	/// The training starts from the current weights and uses the current
+	/// optimizer unless told otherwise.
+	pub fn train(self, samples: Vec<Sample>) -> MentorBuilder {
+		Mentor::new(Disciple::Trained(Box::new(self)), samples, None)
+	}
+
+	/// Resumes the training of this neural network with samples
+	/// pulled in order from the given sample source.
+	pub fn train_from_source<S>(self, source: S) -> MentorBuilder
+		where S: SampleSource + Send + 'static
+	{
+		Mentor::new(Disciple::Trained(Box::new(self)), vec![], Some(BoxedSource::new(source)))
+	}
+}
+
+impl Checkpoint {
+	/// Resumes the training session of this checkpoint with the given samples.
+	///
+	/// The samples should be the same that were used for the training
+	/// that wrote this checkpoint. The training settings, e.g. the criterion,
+	/// are not part of the checkpoint and have to be set again.
+	///
+	/// The state of the optimizer is kept unless told otherwise.
+	pub fn train(self, samples: Vec<Sample>) -> MentorBuilder {
+		Mentor::new(Disciple::Resumed(Box::new(self)), samples, None)
+	}
+
+	/// Resumes the training session of this checkpoint with samples
+	/// pulled in order from the given sample source.
+	///
+	/// The source should provide the same samples in the same order
+	/// as the one used for the training that wrote this checkpoint.
+	pub fn train_from_source<S>(self, source: S) -> MentorBuilder
+		where S: SampleSource + Send + 'static
+	{
+		Mentor::new(Disciple::Resumed(Box::new(self)), vec![], Some(BoxedSource::new(source)))
+	}
+}
+
+/// The neural network to be trained by a mentor.
+#[derive(Debug, Clone)]
+enum Disciple {
+	/// A new neural network with random weights is created from the topology.
+	Untrained(Topology),
+
+	/// The training of an existing neural network is resumed.
+	Trained(Box<NeuralNet>),
+
+	/// The training session of a checkpoint is resumed.
+	Resumed(Box<Checkpoint>),
+}
+
+impl Disciple {
+	/// Returns the number of input neurons.
+	fn len_input(&self) -> usize {
+		match *self {
+			Disciple::Untrained(ref topology) => topology.len_input(),
+			Disciple::Trained(ref net)        => net.len_input(),
+			Disciple::Resumed(ref checkpoint) => checkpoint.net.len_input()
+		}
+	}
+
+	/// Returns the number of output neurons.
+	fn len_output(&self) -> usize {
+		match *self {
+			Disciple::Untrained(ref topology) => topology.len_output(),
+			Disciple::Trained(ref net)        => net.len_output(),
+			Disciple::Resumed(ref checkpoint) => checkpoint.net.len_output()
+		}
+	}
+
+	/// Returns the loss function to be used unless told otherwise.
+	fn default_loss(&self) -> Loss {
+		Loss::for_activation(self.output_activation())
+	}
+
+	/// Returns the number of layers excluding the input layer.
+	fn len_layers(&self) -> usize {
+		match *self {
+			Disciple::Untrained(ref topology) => topology.len_layers(),
+			Disciple::Trained(ref net)        => net.len_layers(),
+			Disciple::Resumed(ref checkpoint) => checkpoint.net.len_layers()
+		}
+	}
+
+	/// Returns the activation function of the output layer.
+	fn output_activation(&self) -> OutputActivation {
+		match *self {
+			Disciple::Untrained(ref topology) => topology.output_activation(),
+			Disciple::Trained(ref net)        => net.output_activation(),
+			Disciple::Resumed(ref checkpoint) => checkpoint.net.output_activation()
+		}
+	}
+
+	/// Returns the optimizer to be used unless told otherwise.
+	fn optimizer(&self) -> Optimizer {
+		match *self {
+			Disciple::Untrained(_)            => Optimizer::default(),
+			Disciple::Trained(ref net)        => net.optimizer(),
+			Disciple::Resumed(ref checkpoint) => checkpoint.net.optimizer()
+		}
+	}
+
+	/// Returns the neural network to be trained and
+	/// the progress of the resumed training session if any.
+	///
+	/// New neural networks draw their weights from the given random number generator.
+	fn into_parts(self, rng: &mut Prng) -> (NeuralNet, Option<Progress>) {
+		match self {
+			Disciple::Untrained(topology) => (NeuralNet::from_topology_using(topology, rng), None),
+			Disciple::Trained(net)        => (*net, None),
+			Disciple::Resumed(checkpoint) => {
+				let checkpoint = *checkpoint;
+				(checkpoint.net, Some(checkpoint.progress))
+			}
+		}
 	}
 }
 
 
 
 mod state {
-	pub trait LearnRateConfigState {}
-	pub trait LearnMomentumConfigState {}
-	pub trait CriterionConfigState {}
-	pub trait SchedulingConfigState {}
-	pub trait LogConfigState {}
+	pub trait LearnRateConfigState: Clone {}
+	pub trait LearnMomentumConfigState: Clone {}
+	pub trait CriterionConfigState: Clone {}
+	pub trait SchedulingConfigState: Clone {}
+	pub trait LogConfigState: Clone {}
+	pub trait OptimizerConfigState: Clone {}
+	pub trait BatchSizeConfigState: Clone {}
+	pub trait ValidationConfigState: Clone {}
+	pub trait HistoryConfigState: Clone {}
+	pub trait OptimizerStateConfigState: Clone {}
+	pub trait SeedConfigState: Clone {}
+	pub trait ScalingConfigState: Clone {}
+	pub trait LossConfigState: Clone {}
+	pub trait RegularizationConfigState: Clone {}
+	pub trait GradientClippingConfigState: Clone {}
+	pub trait RollbackConfigState: Clone {}
 
 	#[derive(Debug, Copy, Clone)]
 	pub struct Unset;
@@ -50,12 +235,34 @@ mod state {
 	impl CriterionConfigState for Unset {}
 	impl SchedulingConfigState for Unset {}
 	impl LogConfigState for Unset {}
+	impl OptimizerConfigState for Unset {}
+	impl BatchSizeConfigState for Unset {}
+	impl ValidationConfigState for Unset {}
+	impl HistoryConfigState for Unset {}
+	impl OptimizerStateConfigState for Unset {}
+	impl SeedConfigState for Unset {}
+	impl ScalingConfigState for Unset {}
+	impl LossConfigState for Unset {}
+	impl RegularizationConfigState for Unset {}
+	impl GradientClippingConfigState for Unset {}
+	impl RollbackConfigState for Unset {}
 
 	impl LearnRateConfigState for Set {}
 	impl LearnMomentumConfigState for Set {}
 	impl CriterionConfigState for Set {}
 	impl SchedulingConfigState for Set {}
 	impl LogConfigState for Set {}
+	impl OptimizerConfigState for Set {}
+	impl BatchSizeConfigState for Set {}
+	impl ValidationConfigState for Set {}
+	impl HistoryConfigState for Set {}
+	impl OptimizerStateConfigState for Set {}
+	impl SeedConfigState for Set {}
+	impl ScalingConfigState for Set {}
+	impl LossConfigState for Set {}
+	impl RegularizationConfigState for Set {}
+	impl GradientClippingConfigState for Set {}
+	impl RollbackConfigState for Set {}
 }
 use self::state::{
 	LearnRateConfigState,
@@ -63,6 +270,17 @@ use self::state::{
 	CriterionConfigState,
 	SchedulingConfigState,
 	LogConfigState,
+	OptimizerConfigState,
+	BatchSizeConfigState,
+	ValidationConfigState,
+	HistoryConfigState,
+	OptimizerStateConfigState,
+	SeedConfigState,
+	ScalingConfigState,
+	LossConfigState,
+	RegularizationConfigState,
+	GradientClippingConfigState,
+	RollbackConfigState,
 
 	Unset,
 	Set
@@ -70,54 +288,110 @@ use self::state::{
 use std::marker::PhantomData;
 
 /// A fresh mentor which is completely uninitialized, yet.
-pub type MentorBuilder = Mentor<Unset, Unset, Unset, Unset, Unset>;
+pub type MentorBuilder = Mentor<Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset, Unset>;
 
 /// Mentor follows the builder pattern to incrementally
 /// build properties for the training session and delay any
 /// expensive computations until the go routine is called.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Mentor<
 	LR: LearnRateConfigState,
 	LM: LearnMomentumConfigState,
 	CR: CriterionConfigState,
 	SC: SchedulingConfigState,
-	LG: LogConfigState >
+	LG: LogConfigState,
+	OP: OptimizerConfigState,
+	BS: BatchSizeConfigState,
+	VS: ValidationConfigState,
+	HI: HistoryConfigState,
+	KS: OptimizerStateConfigState,
+	SD: SeedConfigState,
+	SL: ScalingConfigState,
+	LS: LossConfigState,
+	RG: RegularizationConfigState,
+	GC: GradientClippingConfigState,
+	RB: RollbackConfigState >
 {
 	learn_rate: LearnRateConfig,
 	learn_mom : LearnMomentumConfig,
 	criterion : Criterion,
 	scheduling: Scheduling,
-	disciple  : Topology,
+	disciple  : Disciple,
 	samples   : Vec<Sample>,
+	source    : Option<BoxedSource>,
 	log_config: LogConfig,
+	optimizer : Optimizer,
+	batch_size: BatchSize,
+	validation: Vec<Sample>,
+	history   : Option<u64>,
+	observers : Observers,
+	keep_state: bool,
+	#[cfg(feature = "serde_support")]
+	checkpoint: Option<Checkpointer>,
+	seed      : Option<u64>,
+	scalers   : (Scaler, Scaler),
+	loss      : Option<Loss>,
+	regularization: Vec<Regularization>,
+	clipping  : GradientClipping,
+	rollback  : Option<u64>,
 
-	phantom   : PhantomData<(LR, LM, CR, SC, LG)>
+	phantom   : PhantomData<(LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB)>
 }
 
 impl MentorBuilder {
 	/// Creates a new mentor for the given disciple and
-	/// with the given sample collection (training data).
-	fn new(disciple: Topology, samples: Vec<Sample>) -> MentorBuilder {
+	/// with the given sample collection (training data)
+	/// or the given sample source if any.
+	fn new(disciple: Disciple, samples: Vec<Sample>, source: Option<BoxedSource>) -> MentorBuilder {
+		let optimizer  = disciple.optimizer();
+		let keep_state = matches!(disciple, Disciple::Resumed(_));
+		let scheduling = if source.is_some() { Scheduling::Iterative } else { Scheduling::Random };
 		Mentor {
 			learn_rate: LearnRateConfig::Adapt,
 			learn_mom : LearnMomentumConfig::Adapt,
-			criterion : Criterion::RecentMSE(0.05),
-			scheduling: Scheduling::Random,
+			criterion : Criterion::RecentLoss(0.0025),
+			scheduling: scheduling,
 			disciple  : disciple,
 			samples   : samples,
+			source    : source,
 			log_config: LogConfig::Never,
+			optimizer : optimizer,
+			batch_size: BatchSize::Mini(1),
+			validation: vec![],
+			history   : None,
+			observers : Observers::default(),
+			keep_state: keep_state,
+			#[cfg(feature = "serde_support")]
+			checkpoint: None,
+			seed      : None,
+			scalers   : (Scaler::Identity, Scaler::Identity),
+			loss      : None,
+			regularization: vec![],
+			clipping  : GradientClipping::None,
+			rollback  : None,
 			phantom   : PhantomData
 		}
 	}
 }
 
-impl<LR1, LM1, CR1, SC1, LG1> Mentor<LR1, LM1, CR1, SC1, LG1>
+impl<LR1, LM1, CR1, SC1, LG1, OP1, BS1, VS1, HI1, KS1, SD1, SL1, LS1, RG1, GC1, RB1> Mentor<LR1, LM1, CR1, SC1, LG1, OP1, BS1, VS1, HI1, KS1, SD1, SL1, LS1, RG1, GC1, RB1>
 	where
 		LR1: LearnRateConfigState,
 		LM1: LearnMomentumConfigState,
 		CR1: CriterionConfigState,
 		SC1: SchedulingConfigState,
-		LG1: LogConfigState
+		LG1: LogConfigState,
+		OP1: OptimizerConfigState,
+		BS1: BatchSizeConfigState,
+		VS1: ValidationConfigState,
+		HI1: HistoryConfigState,
+		KS1: OptimizerStateConfigState,
+		SD1: SeedConfigState,
+		SL1: ScalingConfigState,
+		LS1: LossConfigState,
+		RG1: RegularizationConfigState,
+		GC1: GradientClippingConfigState,
+		RB1: RollbackConfigState
 {
 	/// Switches the compile-time type-based state of this mentor.
 	/// 
@@ -127,8 +401,19 @@ impl<LR1, LM1, CR1, SC1, LG1> Mentor<LR1, LM1, CR1, SC1, LG1>
 		LM2: LearnMomentumConfigState,
 		CR2: CriterionConfigState,
 		SC2: SchedulingConfigState,
-		LG2: LogConfigState>
-	(self) -> Mentor<LR2, LM2, CR2, SC2, LG2> {
+		LG2: LogConfigState,
+		OP2: OptimizerConfigState,
+		BS2: BatchSizeConfigState,
+		VS2: ValidationConfigState,
+		HI2: HistoryConfigState,
+		KS2: OptimizerStateConfigState,
+		SD2: SeedConfigState,
+		SL2: ScalingConfigState,
+		LS2: LossConfigState,
+		RG2: RegularizationConfigState,
+		GC2: GradientClippingConfigState,
+		RB2: RollbackConfigState>
+	(self) -> Mentor<LR2, LM2, CR2, SC2, LG2, OP2, BS2, VS2, HI2, KS2, SD2, SL2, LS2, RG2, GC2, RB2> {
 		Mentor{
 			learn_rate: self.learn_rate,
 			learn_mom : self.learn_mom,
@@ -136,25 +421,51 @@ impl<LR1, LM1, CR1, SC1, LG1> Mentor<LR1, LM1, CR1, SC1, LG1>
 			scheduling: self.scheduling,
 			disciple  : self.disciple,
 			samples   : self.samples,
+			source    : self.source,
 			log_config: self.log_config,
+			optimizer : self.optimizer,
+			batch_size: self.batch_size,
+			validation: self.validation,
+			history   : self.history,
+			observers : self.observers,
+			keep_state: self.keep_state,
+			#[cfg(feature = "serde_support")]
+			checkpoint: self.checkpoint,
+			seed      : self.seed,
+			scalers   : self.scalers,
+			loss      : self.loss,
+			regularization: self.regularization,
+			clipping  : self.clipping,
+			rollback  : self.rollback,
 			phantom   : PhantomData
 		}
 	}
 }
 
-impl<LM, CR, SC, LG> Mentor<Unset, LM, CR, SC, LG>
+impl<LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<Unset, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB>
 	where
 		LM: LearnMomentumConfigState,
 		CR: CriterionConfigState,
 		SC: SchedulingConfigState,
-		LG: LogConfigState
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
 {
 	/// Use the given fixed learn rate.
 	///
 	/// Default learn rate is adapting behaviour.
 	/// 
 	/// ***Panics*** if given learn rate is invalid!
-	pub fn learn_rate(mut self, learn_rate: f64) -> Mentor<Set, LM, CR, SC, LG> {
+	pub fn learn_rate(mut self, learn_rate: f64) -> Mentor<Set, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> {
 		self.learn_rate = LearnRateConfig::Fixed(
 			LearnRate::from_f64(learn_rate)
 				.expect("expected valid learn rate"));
@@ -162,19 +473,30 @@ impl<LM, CR, SC, LG> Mentor<Unset, LM, CR, SC, LG>
 	}
 }
 
-impl<LR, CR, SC, LG> Mentor<LR, Unset, CR, SC, LG>
+impl<LR, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, Unset, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB>
 	where
 		LR: LearnRateConfigState,
 		CR: CriterionConfigState,
 		SC: SchedulingConfigState,
-		LG: LogConfigState
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
 {
 	/// Use the given fixed learn momentum.
 	///
-	/// Default learn momentum is fixed at `0.5`.
+	/// Default learn momentum is adapting behaviour.
 	/// 
 	/// ***Panics*** if given learn momentum is invalid
-	pub fn learn_momentum(mut self, learn_momentum: f64) -> Mentor<LR, Set, CR, SC, LG> {
+	pub fn learn_momentum(mut self, learn_momentum: f64) -> Mentor<LR, Set, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> {
 		self.learn_mom = LearnMomentumConfig::Fixed(
 			LearnMomentum::from_f64(learn_momentum)
 				.expect("expected valid learn momentum"));
@@ -182,67 +504,602 @@ impl<LR, CR, SC, LG> Mentor<LR, Unset, CR, SC, LG>
 	}
 }
 
-impl<LR, LM, SC, LG> Mentor<LR, LM, Unset, SC, LG>
+impl<LR, LM, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, Unset, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB>
 	where
 		LR: LearnRateConfigState,
 		LM: LearnMomentumConfigState,
 		SC: SchedulingConfigState,
-		LG: LogConfigState
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
 {
 	/// Use the given criterion.
 	///
-	/// Default criterion is `AvgNetError(0.05)`.
-	pub fn criterion(mut self, criterion: Criterion) -> Mentor<LR, LM, Set, SC, LG> {
+	/// Default criterion is `RecentLoss(0.0025)` which is a recent root mean
+	/// squared error of `0.05` for the default `Loss::MeanSquaredError`.
+	pub fn criterion(mut self, criterion: Criterion) -> Mentor<LR, LM, Set, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> {
 		self.criterion = criterion;
 		self.switch_state()
 	}
 }
 
-impl<LR, LM, CR, LG> Mentor<LR, LM, CR, Unset, LG>
+impl<LR, LM, CR, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, Unset, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB>
 	where
 		LR: LearnRateConfigState,
 		LM: LearnMomentumConfigState,
 		CR: CriterionConfigState,
-		LG: LogConfigState
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
 {
 	/// Use the given scheduling routine.
 	///
 	/// Default scheduling routine is to pick random samples.
-	pub fn scheduling(mut self, kind: Scheduling) -> Mentor<LR, LM, CR, Set, LG> {
+	///
+	/// Samples from sample sources can only be scheduled iteratively.
+	pub fn scheduling(mut self, kind: Scheduling) -> Mentor<LR, LM, CR, Set, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> {
 		self.scheduling = kind;
 		self.switch_state()
 	}
 }
 
-impl<LR, LM, CR, SC> Mentor<LR, LM, CR, SC, Unset>
+impl<LR, LM, CR, SC, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, Unset, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB>
 	where
 		LR: LearnRateConfigState,
 		LM: LearnMomentumConfigState,
 		CR: CriterionConfigState,
 		SC: SchedulingConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
 {
 	/// Use the given logging configuration.
 	/// 
 	/// Default logging configuration is to never log anything.
-	pub fn log_config(mut self, config: LogConfig) -> Mentor<LR, LM, CR, SC, Set> {
+	pub fn log_config(mut self, config: LogConfig) -> Mentor<LR, LM, CR, SC, Set, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> {
 		self.log_config = config;
 		self.switch_state()
 	}
 }
 
-impl<LR, LM, CR, SC, LG> Mentor<LR, LM, CR, SC, LG>
+impl<LR, LM, CR, SC, LG, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, Unset, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Use the given optimizer to update the weights.
+	///
+	/// Default optimizer is gradient descent with momentum or the
+	/// current optimizer of a neural network whose training is resumed.
+	pub fn optimizer(mut self, optimizer: Optimizer) -> Mentor<LR, LM, CR, SC, LG, Set, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> {
+		self.optimizer = optimizer;
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, Unset, VS, HI, KS, SD, SL, LS, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Use the given batch size.
+	///
+	/// Default batch size is to update the weights after every sample.
+	pub fn batch_size(mut self, batch_size: BatchSize) -> Mentor<LR, LM, CR, SC, LG, OP, Set, VS, HI, KS, SD, SL, LS, RG, GC, RB> {
+		self.batch_size = batch_size;
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, Unset, HI, KS, SD, SL, LS, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Use the given held-out samples to validate the neural network
+	/// once per pass over all training samples.
+	///
+	/// When validation samples are given the training returns the
+	/// neural network with the lowest validation error seen so far
+	/// instead of the last one. Required by `Criterion::EarlyStopping`.
+	///
+	/// By default no validation is done.
+	pub fn validation(mut self, samples: Vec<Sample>) -> Mentor<LR, LM, CR, SC, LG, OP, BS, Set, HI, KS, SD, SL, LS, RG, GC, RB> {
+		self.validation = samples;
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, Unset, KS, SD, SL, LS, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Record the training stats every time the given
+	/// amount of iterations has passed.
+	///
+	/// The recorded stats are part of the returned `TrainingReport`.
+	///
+	/// By default no history is recorded.
+	pub fn history_interval(mut self, interval: u64) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, Set, KS, SD, SL, LS, RG, GC, RB> {
+		self.history = Some(interval);
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, Unset, SD, SL, LS, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Keep the state of the optimizer, e.g. the momentum, when
+	/// resuming the training of an already trained neural network.
+	///
+	/// The state is only kept if the optimizer stays the same.
+	///
+	/// By default the optimizer state is reset.
+	pub fn keep_optimizer_state(mut self, keep: bool) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, Set, SD, SL, LS, RG, GC, RB> {
+		self.keep_state = keep;
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, Unset, SL, LS, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Seed the random number generator used for the initial weights
+	/// of new neural networks and for random sample scheduling.
+	///
+	/// Training runs with the same seed, samples and settings produce
+	/// the same neural network on the same machine unless they
+	/// depend on time, e.g. via `Criterion::TimeOut`.
+	pub fn seed(mut self, seed: u64) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, Set, SL, LS, RG, GC, RB> {
+		self.seed = Some(seed);
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, Unset, LS, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Scale the inputs and targets of all samples with the given scalers.
+	///
+	/// The scalers are fitted to the training samples before the training starts.
+	/// The trained neural network keeps the fitted scalings so that `predict`
+	/// scales its inputs and unscales its outputs the same way.
+	/// Validation errors are computed on scaled targets, too.
+	///
+	/// Neural networks that already have been trained with scaled samples
+	/// keep their scalings and ignore the given scalers.
+	///
+	/// By default samples are not scaled.
+	pub fn scaling(mut self, inputs: Scaler, targets: Scaler) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, Set, LS, RG, GC, RB> {
+		self.scalers = (inputs, targets);
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, Unset, RG, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Use the given loss function to compute the output gradients
+	/// and the errors reported by stats and checked by criteria.
+	///
+	/// By default `Loss::CategoricalCrossEntropy` is used for `OutputActivation::SoftMax`
+	/// output layers and `Loss::MeanSquaredError` for all others.
+	pub fn loss(mut self, loss: Loss) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, Set, RG, GC, RB> {
+		self.loss = Some(loss);
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, Unset, GC, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
+{
+	/// Regularize the weights of all layers with the given regularization.
+	///
+	/// The L1 and L2 penalties are included in the loss of the stats
+	/// that is checked by criteria.
+	/// The weights of bias neurons are never regularized.
+	///
+	/// By default weights are not regularized.
+	pub fn regularization(mut self, regularization: Regularization) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, Set, GC, RB> {
+		self.regularization = vec![regularization];
+		self.switch_state()
+	}
+
+	/// Regularize the weights of every layer with the respective given regularization.
+	///
+	/// There must be exactly one regularization for every layer excluding the input layer.
+	///
+	/// See `regularization` for details.
+	pub fn layer_regularization(mut self, regularization: Vec<Regularization>) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, Set, GC, RB> {
+		self.regularization = regularization;
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, Unset, RB>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		RB: RollbackConfigState
+{
+	/// Clip the weight gradients of all layers with the given gradient clipping
+	/// before they are applied to the weights.
+	///
+	/// This prevents the weights from blowing up with unbounded
+	/// activation functions such as `ReLU`, `SoftPlus` or `Identity`.
+	///
+	/// By default gradients are not clipped.
+	pub fn gradient_clipping(mut self, clipping: GradientClipping) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, Set, RB> {
+		self.clipping = clipping;
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, Unset>
+	where
+		LR: LearnRateConfigState,
+		LM: LearnMomentumConfigState,
+		CR: CriterionConfigState,
+		SC: SchedulingConfigState,
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState
+{
+	/// Roll back to the latest state of the training with only finite values
+	/// after the training diverged, keeping such a state in memory every
+	/// time the given amount of iterations has passed.
+	///
+	/// The learn rate is halved with every rollback, even if a fixed
+	/// learn rate was given, since continuing with the learn rate the
+	/// training diverged with would most likely diverge again.
+	/// After 10 rollbacks the training fails with `ErrorKind::TrainingDiverged`.
+	///
+	/// By default a diverged training fails immediately.
+	pub fn rollback_interval(mut self, interval: u64) -> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, Set> {
+		self.rollback = Some(interval);
+		self.switch_state()
+	}
+}
+
+impl<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB> Mentor<LR, LM, CR, SC, LG, OP, BS, VS, HI, KS, SD, SL, LS, RG, GC, RB>
 	where
 		LR: LearnRateConfigState,
 		LM: LearnMomentumConfigState,
 		CR: CriterionConfigState,
 		SC: SchedulingConfigState,
-		LG: LogConfigState
+		LG: LogConfigState,
+		OP: OptimizerConfigState,
+		BS: BatchSizeConfigState,
+		VS: ValidationConfigState,
+		HI: HistoryConfigState,
+		KS: OptimizerStateConfigState,
+		SD: SeedConfigState,
+		SL: ScalingConfigState,
+		LS: LossConfigState,
+		RG: RegularizationConfigState,
+		GC: GradientClippingConfigState,
+		RB: RollbackConfigState
 {
+	/// Add the given observer that is notified after every training iteration.
+	///
+	/// Observers may request to stop the training.
+	pub fn observer<O>(mut self, observer: O) -> Self
+		where O: TrainingObserver + Send + 'static
+	{
+		self.observers.push(observer);
+		self
+	}
+
+	/// Periodically save the full training state to the file at the given
+	/// path every time the given amount of iterations has passed.
+	///
+	/// The training can be resumed from the saved `Checkpoint`.
+	///
+	/// Cross validations write the checkpoints of every fold to the
+	/// given path suffixed with `.fold-<index>`, e.g. `net.ckpt.fold-0`.
+	#[cfg(feature = "serde_support")]
+	pub fn checkpoint<P>(mut self, path: P, interval: u64) -> Self
+		where P: Into<PathBuf>
+	{
+		self.checkpoint = Some(Checkpointer::new(path.into(), interval));
+		self
+	}
+
+	/// Runs a k-fold cross validation with the given number of folds
+	/// over the samples of this mentor.
+	///
+	/// The samples are shuffled with the given seed and split into folds.
+	/// For every fold a neural network is trained with a copy of this
+	/// mentor's configuration on the samples of all other folds and then
+	/// tested on the samples of the fold.
+	///
+	/// Test errors are losses of the unscaled predictions.
+	///
+	/// Observers added to this mentor are not notified since every fold
+	/// requires its own observers, see `cross_validate_observed`.
+	///
+	/// # Errors
+	///
+	/// - `ErrorKind::InvalidFolds` if there are less than two folds or more folds
+	///   than samples, e.g. for mentors with samples from a sample source.
+	/// - Every error of `go` for any of the folds.
+	pub fn cross_validate(self, folds: usize, seed: u64) -> Result<CrossValidation> {
+		self.cross_validate_folds(folds, seed, |_, _| ())
+	}
+
+	/// Runs a k-fold cross validation like `cross_validate` where the training
+	/// of every fold is observed by its own observer created by the given
+	/// function from the zero-based index of the fold.
+	pub fn cross_validate_observed<F, O>(self, folds: usize, seed: u64, mut observer: F) -> Result<CrossValidation>
+		where F: FnMut(usize) -> O,
+		      O: TrainingObserver + Send + 'static
+	{
+		self.cross_validate_folds(folds, seed, |fold, observers| observers.push(observer(fold)))
+	}
+
+	/// Runs a k-fold cross validation with the observers of every
+	/// fold set up by the given function.
+	fn cross_validate_folds<F>(mut self, folds: usize, seed: u64, mut observe: F) -> Result<CrossValidation>
+		where F: FnMut(usize, &mut Observers)
+	{
+		use traits::Predict;
+		let samples = ::std::mem::take(&mut self.samples);
+		let loss    = self.selected_loss();
+		let folds = Folds::new(&samples, folds, seed)?
+			.enumerate()
+			.map(|(fold, (train, test))| {
+				let mut mentor = self.for_fold(fold, train);
+				observe(fold, &mut mentor.observers);
+				let (mut net, report) = mentor.go()?;
+				let sum = test
+					.iter()
+					.map(|sample| loss.error(net.predict(sample.input.view()), sample.target.view()))
+					.sum::<f64>();
+				Ok(FoldReport{
+					report   : report,
+					test_loss: sum / test.len() as f64
+				})
+			})
+			.collect::<Result<Vec<_>>>()?;
+		Ok(CrossValidation::new(folds))
+	}
+
+	/// Returns a mentor with the configuration of this mentor for the given
+	/// fold and its training samples and without any observers.
+	#[cfg_attr(not(feature = "serde_support"), allow(unused_variables))]
+	fn for_fold(&self, fold: usize, samples: Vec<Sample>) -> Self {
+		Mentor{
+			learn_rate: self.learn_rate,
+			learn_mom : self.learn_mom,
+			criterion : self.criterion.clone(),
+			scheduling: self.scheduling,
+			disciple  : self.disciple.clone(),
+			samples   : samples,
+			source    : None,
+			log_config: self.log_config,
+			optimizer : self.optimizer.clone(),
+			batch_size: self.batch_size,
+			validation: self.validation.clone(),
+			history   : self.history,
+			observers : Observers::default(),
+			keep_state: self.keep_state,
+			#[cfg(feature = "serde_support")]
+			checkpoint: self.checkpoint.as_ref().map(|checkpoint| checkpoint.for_fold(fold)),
+			seed      : self.seed,
+			scalers   : self.scalers.clone(),
+			loss      : self.loss,
+			regularization: self.regularization.clone(),
+			clipping  : self.clipping,
+			rollback  : self.rollback,
+			phantom   : PhantomData
+		}
+	}
+
+	/// Returns the loss function to train with.
+	fn selected_loss(&self) -> Loss {
+		self.loss.unwrap_or_else(|| self.disciple.default_loss())
+	}
+
+	/// Checks that all regularizations are valid and that there
+	/// is either one for all layers or one for every layer.
+	fn check_regularization(&self) -> Result<()> {
+		let len = self.regularization.len();
+		if len > 1 && len != self.disciple.len_layers() {
+			return Err(InvalidRegularization)
+		}
+		let max_rate = self.learn_rate.max_rate();
+		self.regularization.iter().try_for_each(|regularization| regularization.check_validity(max_rate))
+	}
+
+	/// Returns the regularization of every layer to train with.
+	fn regularization_per_layer(&self) -> Vec<Regularization> {
+		let len_layers = self.disciple.len_layers();
+		match self.regularization.len() {
+			0 => vec![Regularization::default(); len_layers],
+			1 => vec![self.regularization[0]; len_layers],
+			_ => self.regularization.clone()
+		}
+	}
+
 	/// Validate all sample input and target sizes.
 	fn validate_samples(&self) -> Result<()> {
 		let req_inputs = self.disciple.len_input();
 		let req_outputs = self.disciple.len_output();
-		for sample in &self.samples {
+		for sample in self.samples.iter().chain(&self.validation) {
 			if sample.input.len() != req_inputs {
 				return Err(InvalidSampleInputSize);
 			}
@@ -250,6 +1107,21 @@ impl<LR, LM, CR, SC, LG> Mentor<LR, LM, CR, SC, LG>
 				return Err(InvalidSampleTargetSize);
 			}
 		}
+		if !self.samples.iter().all(Sample::has_valid_weight) {
+			return Err(InvalidSampleWeight);
+		}
+		if matches!(self.scheduling, Scheduling::Weighted) &&
+			self.samples.iter().all(|sample| sample.weight == 0.0)
+		{
+			return Err(InvalidSampleWeight);
+		}
+		// Empty sample sources are reported on their own.
+		let num_samples = self.source.as_ref().map_or(self.samples.len(), BoxedSource::len);
+		if let BatchSize::Mini(size) = self.batch_size {
+			if num_samples > 0 && size > num_samples {
+				return Err(InvalidBatchSize);
+			}
+		}
 		Ok(())
 	}
 
@@ -259,27 +1131,131 @@ impl<LR, LM, CR, SC, LG> Mentor<LR, LM, CR, SC, LG>
 	///
 	/// Then starts the learning procedure and returns the fully trained
 	/// neural network (Prophet) that is capable to predict data if no
-	/// errors occured while training it.
-	pub fn go(self) -> Result<NeuralNet> {
+	/// errors occured while training it together with a report
+	/// about the training session.
+	pub fn go(self) -> Result<(NeuralNet, TrainingReport)> {
 		self.criterion.check_validity()?;
+		self.optimizer.check_validity()?;
+		self.batch_size.check_validity()?;
 		self.validate_samples()?;
-		self.start_training().start()
+		self.scalers.0.check_validity(self.disciple.len_input())?;
+		self.scalers.1.check_validity(self.disciple.len_output())?;
+		self.scalers.1.check_targets(self.disciple.output_activation())?;
+		self.selected_loss().check_validity(self.disciple.output_activation())?;
+		self.check_regularization()?;
+		self.clipping.check_validity()?;
+		if let Some(ref source) = self.source {
+			if !matches!(self.scheduling, Scheduling::Iterative) {
+				return Err(InvalidScheduling)
+			}
+			if source.is_empty() {
+				return Err(EmptySampleSource)
+			}
+		}
+		if self.criterion.requires_validation() && self.validation.is_empty() {
+			return Err(MissingValidationSamples)
+		}
+		if self.history == Some(0) {
+			return Err(InvalidHistoryInterval)
+		}
+		if self.rollback == Some(0) {
+			return Err(InvalidRollbackInterval)
+		}
+		#[cfg(feature = "serde_support")]
+		{
+			if self.checkpoint.as_ref().map_or(false, |checkpoint| !checkpoint.is_valid()) {
+				return Err(InvalidCheckpointInterval)
+			}
+		}
+		self.start_training()?.start()
 	}
 
 	/// Consumes this mentor and starts a training session.
 	/// 
 	/// This process computes all required structures for the training session.
-	fn start_training(self) -> Training {
-		Training {
-			disciple : NeuralNet::from_topology(self.disciple),
-			scheduler: SampleScheduler::from_samples(self.scheduling, self.samples),
+	fn start_training(mut self) -> Result<Training> {
+		let num_samples = self.source.as_ref().map_or(self.samples.len(), BoxedSource::len);
+		let len_input   = self.disciple.len_input();
+		let len_output  = self.disciple.len_output();
+		let loss        = self.selected_loss();
+		let regularize  = self.regularization_per_layer();
+
+		let mut rng = rng::new(self.seed);
+		let (mut disciple, progress) = self.disciple.into_parts(&mut rng);
+		if self.keep_state {
+			disciple.set_optimizer(self.optimizer);
+		}
+		else {
+			disciple.reset_optimizer(self.optimizer);
+		}
+		disciple.set_regularization(&regularize);
+		disciple.set_clipping(self.clipping);
+
+		// Drawn only if required to keep the sample scheduling of seeded trainings.
+		let dropout = if disciple.has_dropout() { Some(rng.gen()) } else { None };
+
+		// Scalers are fitted once unless the neural network already has scalings.
+		// Samples in memory are scaled once, samples from sources whenever they are pulled.
+		if disciple.scaling().is_none() {
+			disciple.set_scaling(fit_scaling(&self.scalers, &self.samples, self.source.as_mut(), len_input, len_output)?);
+		}
+		let scaling = disciple.scaling().clone();
+		if !scaling.is_none() {
+			for sample in self.samples.iter_mut().chain(self.validation.iter_mut()) {
+				scaling.apply(sample)
+			}
+		}
+
+		// Full batches of samples in memory never change, so they are filled only once.
+		// Full batches of samples from sources are filled anew for every session.
+		let (batch_inputs, batch_targets, sessions_per_epoch) = match self.batch_size {
+			BatchSize::Mini(size) => (
+				Array2::zeros((size, len_input)),
+				Array2::zeros((size, len_output)),
+				num_samples / size
+			),
+			BatchSize::Full if self.source.is_some() => (
+				Array2::zeros((num_samples, len_input)),
+				Array2::zeros((num_samples, len_output)),
+				1
+			),
+			BatchSize::Full => {
+				let mut inputs  = Array2::zeros((num_samples, len_input));
+				let mut targets = Array2::zeros((num_samples, len_output));
+				for (sample, mut input, mut target) in
+					izip!(&self.samples, inputs.genrows_mut(), targets.genrows_mut())
+				{
+					input.assign(&sample.input);
+					target.assign(&sample.target);
+				}
+				(inputs, targets, 1)
+			}
+		};
+
+		let mut observers = self.observers;
+		self.log_config.add_logger(&mut observers);
+
+		let mut training = Training {
+			validator: Validator::new(self.validation, num_samples as u64),
+			history  : History::new(self.history),
+
+			disciple : disciple,
+			scheduler: match self.source {
+				Some(source) => SampleScheduler::from_source(source, len_input, len_output, scaling),
+				None         => SampleScheduler::from_samples(self.scheduling, self.samples, rng)
+			},
 
 			cfg: Config{
 				learn_rate: self.learn_rate,
 				learn_mom : self.learn_mom,
-				criterion : self.criterion
+				criterion : self.criterion,
+				batch_size: self.batch_size,
+				loss      : loss
 			},
 
+			batch_inputs : batch_inputs,
+			batch_targets: batch_targets,
+
 			learn_rate: match self.learn_rate {
 				LearnRateConfig::Adapt    => LearnRate::default(),
 				LearnRateConfig::Fixed(r) => r
@@ -290,15 +1266,68 @@ impl<LR, LM, CR, SC, LG> Mentor<LR, LM, CR, SC, LG>
 				LearnMomentumConfig::Fixed(m) => m
 			},
 
+			rate_adapter    : LearnRateAdapter::new(sessions_per_epoch),
+			momentum_adapter: LearnMomentumAdapter::new(sessions_per_epoch),
+
 			iterations: Iteration::default(),
 			starttime : SystemTime::now(),
 			deviation : Deviation::default(),
 
-			logger: Logger::from(self.log_config)
+			observers: observers,
+			dropout  : dropout,
+			rollback : self.rollback.map(Rollback::new),
+
+			#[cfg(feature = "serde_support")]
+			checkpointer: self.checkpoint
+		};
+		if let Some(progress) = progress {
+			training.restore(progress)
 		}
+		Ok(training)
 	}
 }
 
+/// Fits the given input and target scalers to the samples in memory
+/// or to one pass over the samples of the given source.
+fn fit_scaling(scalers   : &(Scaler, Scaler),
+               samples   : &[Sample],
+               source    : Option<&mut BoxedSource>,
+               len_input : usize,
+               len_output: usize)
+               -> Result<SampleScaling>
+{
+	if scalers.0 == Scaler::Identity && scalers.1 == Scaler::Identity {
+		return Ok(SampleScaling::default())
+	}
+	let mut inputs  = ScalingFit::new(len_input);
+	let mut targets = ScalingFit::new(len_output);
+	match source {
+		Some(source) => {
+			for _ in 0..source.len() {
+				let sample = source.next_sample()?.ok_or(EmptySampleSource)?;
+				if sample.input.len() != len_input {
+					return Err(InvalidSampleInputSize)
+				}
+				if sample.target.len() != len_output {
+					return Err(InvalidSampleTargetSize)
+				}
+				inputs.add(sample.input.view());
+				targets.add(sample.target.view());
+			}
+		}
+		None => {
+			for sample in samples {
+				inputs.add(sample.input.view());
+				targets.add(sample.target.view());
+			}
+		}
+	}
+	Ok(SampleScaling{
+		inputs : inputs.finish(&scalers.0),
+		targets: targets.finish(&scalers.1)
+	})
+}
+
 /// A very simple type that can count upwards and
 /// is comparable to other instances of itself.
 ///
@@ -311,20 +1340,27 @@ impl Iteration {
 	fn bump(&mut self) {
 		self.0 += 1
 	}
+
+	/// Bumps the iteration count by the given amount.
+	fn bump_by(&mut self, amount: u64) {
+		self.0 += amount
+	}
 }
 
 /// Config parameters for mentor objects used throughtout a training session.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 struct Config {
 	pub learn_rate: LearnRateConfig,
 	pub learn_mom : LearnMomentumConfig,
-	pub criterion : Criterion
+	pub criterion : Criterion,
+	pub batch_size: BatchSize,
+	pub loss      : Loss
 }
 
 /// A training session trains a neural network and stops only
 /// after the neural networks training stats meet certain 
 /// predefined criteria.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Training {
 	cfg       : Config,
 	disciple  : NeuralNet,
@@ -334,49 +1370,143 @@ pub struct Training {
 	starttime : SystemTime,
 	learn_rate: LearnRate,
 	learn_mom : LearnMomentum,
-	logger    : Logger
+	observers : Observers,
+
+	batch_inputs : Array2<f32>,
+	batch_targets: Array2<f32>,
+
+	rate_adapter    : LearnRateAdapter,
+	momentum_adapter: LearnMomentumAdapter,
+
+	validator: Option<Validator>,
+	history  : Option<History>,
+
+	/// Draws the dropout masks if any layer drops out outputs.
+	dropout: Option<Prng>,
+
+	/// Keeps the latest finite state to roll back to after diverging.
+	rollback: Option<Rollback>,
+
+	#[cfg(feature = "serde_support")]
+	checkpointer: Option<Checkpointer>
 }
 
 impl Training {
-	fn is_done(&self) -> bool {
+	/// Returns the criterion that holds the learning process
+	/// or `None` if the training should go on.
+	///
+	/// For composite criteria this is the sub-criterion that has
+	/// been met or the whole `All` criterion if all of them are met.
+	fn stop_reason(&self) -> Option<Criterion> {
+		self.fired_criterion(&self.cfg.criterion)
+	}
+
+	fn fired_criterion(&self, criterion: &Criterion) -> Option<Criterion> {
 		use mentor::configs::Criterion::*;
-		match self.cfg.criterion {
+		let done = match *criterion {
 			TimeOut(duration) => {
 				self.starttime.elapsed().unwrap() >= duration
 			},
 			Iterations(limit) => {
-				self.iterations.0 == limit
+				self.iterations.0 >= limit
 			},
-			LatestMSE(target) => {
-				self.deviation.latest_mse() <= target
+			Epochs(limit) => {
+				self.epochs() >= limit
+			},
+			LatestLoss(target) => {
+				self.deviation.latest_loss() <= target
+			}
+			RecentLoss(target) => {
+				self.deviation.recent_loss() <= target
+			}
+			EarlyStopping(patience) => {
+				self.validator.as_ref().map_or(false, |v| v.stalls() >= patience)
 			}
-			RecentMSE(target) => {
-				self.deviation.recent_mse() <= target
+			Any(ref criteria) => {
+				return criteria.iter().find_map(|criterion| self.fired_criterion(criterion))
 			}
+			All(ref criteria) => {
+				criteria.iter().all(|criterion| self.fired_criterion(criterion).is_some())
+			}
+		};
+		if done { Some(criterion.clone()) } else { None }
+	}
+
+	/// Returns the number of epochs passed so far.
+	fn epochs(&self) -> u64 {
+		self.iterations.0 / self.scheduler.len() as u64
+	}
+
+	fn session(&mut self) -> Result<()> {
+		match self.cfg.batch_size {
+			BatchSize::Mini(1) => self.session_single()?,
+			BatchSize::Mini(_) => {
+				self.fill_batch()?;
+				self.session_batch()
+			}
+			BatchSize::Full => {
+				if self.scheduler.is_streamed() {
+					self.fill_batch()?;
+				}
+				self.session_batch()
+			}
+		}
+		Ok(())
+	}
+
+	/// Learns from a single scheduled sample.
+	fn session_single(&mut self) -> Result<()> {
+		let sample  = self.scheduler.next_sample()?;
+		let penalty = self.disciple.penalty();
+		{
+			let output = match self.dropout {
+				Some(ref mut rng) => self.disciple.feed_forward_dropout(sample.input, rng),
+				None              => self.disciple.feed_forward(sample.input)
+			};
+			self.deviation.update(self.cfg.loss, output, sample.target, penalty);
 		}
+		self.disciple.update_gradients(sample.target, self.cfg.loss);
+		self.disciple.update_weights(sample.input, self.learn_rate, self.learn_mom);
+		self.iterations.bump();
+		Ok(())
 	}
 
-	fn session(&mut self) {
+	/// Fills the batch with the next scheduled samples.
+	fn fill_batch(&mut self) -> Result<()> {
+		for (mut input, mut target) in
+			izip!(self.batch_inputs.genrows_mut(), self.batch_targets.genrows_mut())
 		{
-			let sample = self.scheduler.next_sample();
-			{
-				let output = self.disciple.predict(sample.input);
-				self.deviation.update(output, sample.target);
-			}
-			self.disciple.update_gradients(sample.target);
-			self.disciple.update_weights(sample.input, self.learn_rate, self.learn_mom);
-			self.iterations.bump();
+			let sample = self.scheduler.next_sample()?;
+			input.assign(&sample.input);
+			target.assign(&sample.target);
+		}
+		Ok(())
+	}
+
+	/// Learns from all samples of the current batch at once.
+	fn session_batch(&mut self) {
+		let penalty = self.disciple.penalty();
+		{
+			let outputs = match self.dropout {
+				Some(ref mut rng) => self.disciple.feed_forward_batch_dropout(self.batch_inputs.view(), rng),
+				None              => self.disciple.predict_batch(self.batch_inputs.view())
+			};
+			self.deviation.update_batch(self.cfg.loss, outputs, self.batch_targets.view(), penalty);
 		}
-		self.try_log();
+		self.disciple.update_gradients_batch(self.batch_targets.view(), self.cfg.loss);
+		self.disciple.update_weights_batch(self.batch_inputs.view(), self.learn_rate, self.learn_mom);
+		self.iterations.bump_by(self.batch_inputs.rows() as u64);
 	}
 
 	fn update_learn_rate(&mut self) {
 		use self::LearnRateConfig::*;
 		match self.cfg.learn_rate {
 			Adapt => {
-				// TODO: not yet implemented
+				self.learn_rate = self.rate_adapter.adapt(self.learn_rate, &self.deviation)
+			}
+			Fixed(_) => {
+				// nothing to do here!
 			}
-			Fixed(_) => return // nothing to do here!
 		}
 	}
 
@@ -384,33 +1514,219 @@ impl Training {
 		use self::LearnMomentumConfig::*;
 		match self.cfg.learn_mom {
 			Adapt => {
-				// TODO: not yet implemented
+				self.learn_mom = self.momentum_adapter.adapt(self.learn_mom, &self.deviation)
+			}
+			Fixed(_) => {
+				// nothing to do here!
 			}
-			Fixed(_) => return // nothing to do here!
 		}
 	}
 
 	fn stats(&self) -> Stats {
 		Stats{
 			iterations  : self.iterations.0,
+			epochs      : self.epochs(),
 			elapsed_time: self.starttime.elapsed().expect("time must be valid!"),
-			latest_mse  : self.deviation.latest_mse(),
-			recent_mse  : self.deviation.recent_mse()
+			latest_loss : self.deviation.latest_loss(),
+			recent_loss : self.deviation.recent_loss(),
+			learn_rate  : f64::from(self.learn_rate.0),
+			learn_rate_policy: self.cfg.learn_rate.policy(),
+			learn_mom   : f64::from(self.learn_mom.0),
+			validation_loss: self.validator.as_ref().and_then(Validator::latest_loss)
+		}
+	}
+
+	fn try_validate(&mut self) {
+		if let Some(ref mut validator) = self.validator {
+			validator.try_validate(self.iterations.0, &mut self.disciple, self.cfg.loss)
+		}
+	}
+
+	fn try_record(&mut self) {
+		if self.history.as_ref().map_or(false, |history| history.is_due(self.iterations.0)) {
+			let stats = self.stats();
+			if let Some(ref mut history) = self.history {
+				history.record(stats)
+			}
+		}
+	}
+
+	/// Restores the progress of a resumed training session.
+	///
+	/// Learn rate and momentum are only restored if they are adapted.
+	fn restore(&mut self, progress: Progress) {
+		self.iterations = Iteration(progress.iterations);
+		self.starttime  = SystemTime::now() - progress.elapsed_time;
+		self.deviation  = progress.deviation;
+		if let LearnRateConfig::Adapt = self.cfg.learn_rate {
+			self.learn_rate = progress.learn_rate;
+		}
+		if let LearnMomentumConfig::Adapt = self.cfg.learn_mom {
+			self.learn_mom = progress.learn_mom;
+		}
+		self.rate_adapter     = progress.rate_adapter;
+		self.momentum_adapter = progress.momentum_adapter;
+		self.scheduler.set_state(progress.scheduler);
+		if self.dropout.is_some() && progress.dropout.is_some() {
+			self.dropout = progress.dropout;
+		}
+		if let (Some(validator), Some(saved)) = (self.validator.as_mut(), progress.validator) {
+			validator.resume(saved)
+		}
+		if self.history.is_some() && progress.history.is_some() {
+			self.history = progress.history;
+		}
+		#[cfg(feature = "serde_support")]
+		{
+			if let Some(ref mut checkpointer) = self.checkpointer {
+				checkpointer.skip_to(progress.iterations)
+			}
+		}
+	}
+
+	/// Returns a checkpoint of the current training state.
+	fn checkpoint(&self) -> Checkpoint {
+		Checkpoint{
+			net: self.disciple.clone(),
+			progress: Progress{
+				iterations      : self.iterations.0,
+				elapsed_time    : self.starttime.elapsed().expect("time must be valid!"),
+				deviation       : self.deviation,
+				learn_rate      : self.learn_rate,
+				learn_mom       : self.learn_mom,
+				rate_adapter    : self.rate_adapter,
+				momentum_adapter: self.momentum_adapter,
+				scheduler       : self.scheduler.state(),
+				dropout         : self.dropout.clone(),
+				validator       : self.validator.as_ref().map(|validator| validator.progress().clone()),
+				history         : self.history.clone()
+			}
+		}
+	}
+
+	/// Returns `true` if a checkpoint is due to be saved.
+	#[cfg(feature = "serde_support")]
+	fn checkpoint_due(&self) -> bool {
+		self.checkpointer.as_ref().map_or(false, |c| c.is_due(self.iterations.0))
+	}
+
+	/// Returns `true` if a checkpoint is due to be saved.
+	#[cfg(not(feature = "serde_support"))]
+	fn checkpoint_due(&self) -> bool {
+		false
+	}
+
+	/// Returns `true` if a training state is due to be kept to roll back to.
+	fn rollback_due(&self) -> bool {
+		self.rollback.as_ref().map_or(false, |r| r.is_due(self.iterations.0))
+	}
+
+	/// Saves a checkpoint of the current training state if one is due.
+	#[cfg(feature = "serde_support")]
+	fn try_checkpoint(&mut self) -> Result<()> {
+		if !self.checkpoint_due() {
+			return Ok(())
+		}
+		let checkpoint = self.checkpoint();
+		match self.checkpointer {
+			Some(ref mut checkpointer) => checkpointer.save(&checkpoint),
+			None                       => Ok(())
+		}
+	}
+
+	/// Returns the index of the diverged layer if the training diverged.
+	///
+	/// Only the latest loss is checked unless a state of the training is due to be
+	/// kept, so that all layers are only scanned to attribute the divergence and
+	/// to never keep a diverged state. A non-finite loss of a neural network with
+	/// only finite values is attributed to the output layer.
+	fn find_diverged_layer(&self) -> Option<usize> {
+		let loss_diverged = !self.deviation.latest_loss().is_finite();
+		let state_due     = self.rollback_due() || self.checkpoint_due();
+		if !loss_diverged && !state_due {
+			return None
+		}
+		match self.disciple.find_non_finite_layer() {
+			None if loss_diverged => Some(self.disciple.len_layers() - 1),
+			layer                 => layer
+		}
+	}
+
+	/// Keeps the current training state to roll back to if one is due.
+	///
+	/// Must only be called while all values of the neural network are finite.
+	fn try_keep_rollback(&mut self) {
+		if self.rollback_due() {
+			let checkpoint = self.checkpoint();
+			if let Some(ref mut rollback) = self.rollback {
+				rollback.save(checkpoint)
+			}
 		}
 	}
 
-	fn try_log(&mut self) {
+	/// Rolls back the neural network and its error to the latest finite
+	/// training state with a reduced learn rate after the given layer diverged.
+	///
+	/// Iterations keep counting and the sample scheduling goes on.
+	///
+	/// Fails with `TrainingDiverged` if no rollback is possible.
+	fn roll_back(&mut self, layer: usize) -> Result<()> {
+		let diverged = TrainingDiverged{iteration: self.iterations.0, layer: layer};
+		let checkpoint = self.rollback
+			.as_mut()
+			.and_then(Rollback::roll_back)
+			.ok_or(diverged)?;
+		let progress = checkpoint.progress;
+		self.disciple         = checkpoint.net;
+		self.deviation        = progress.deviation;
+		self.rate_adapter     = progress.rate_adapter;
+		self.momentum_adapter = progress.momentum_adapter;
+		self.learn_rate       = Rollback::reduced(self.learn_rate);
+		if let LearnMomentumConfig::Adapt = self.cfg.learn_mom {
+			self.learn_mom = progress.learn_mom;
+		}
+		Ok(())
+	}
+
+	fn notify_observers(&mut self) -> Observation {
+		if self.observers.is_empty() {
+			return Observation::Continue
+		}
 		let stats = self.stats();
-		self.logger.try_log(stats)
+		self.observers.notify(&stats, &self.disciple)
 	}
 
-	fn start(mut self) -> Result<NeuralNet> {
-		loop {
+	fn start(mut self) -> Result<(NeuralNet, TrainingReport)> {
+		self.try_keep_rollback();
+		let stop_reason = loop {
 			self.update_learn_rate();
 			self.update_learn_momentum();
-			self.session();
-			if self.is_done() { break }
-		}
-		Ok(self.disciple)
+			self.session()?;
+			if let Some(layer) = self.find_diverged_layer() {
+				self.roll_back(layer)?;
+				continue
+			}
+			self.try_keep_rollback();
+			self.try_validate();
+			self.try_record();
+			#[cfg(feature = "serde_support")]
+			self.try_checkpoint()?;
+			if self.notify_observers() == Observation::Stop {
+				break StopReason::Observer
+			}
+			if let Some(reason) = self.stop_reason() {
+				break StopReason::Criterion(reason)
+			}
+		};
+		let report = TrainingReport{
+			stats      : self.stats(),
+			stop_reason: stop_reason,
+			history    : self.history.map(History::into_records).unwrap_or_default()
+		};
+		let net = match self.validator {
+			Some(validator) => validator.into_best().unwrap_or(self.disciple),
+			None            => self.disciple
+		};
+		Ok((net, report))
 	}
 }
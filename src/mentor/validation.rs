@@ -0,0 +1,165 @@
+use std::f64;
+
+use neural_net::NeuralNet;
+use mentor::samples::Sample;
+use loss::Loss;
+
+/// Relative decrease of the validation error required to count as improvement.
+const MIN_IMPROVEMENT: f64 = 0.001;
+
+/// Evaluates a neural network under training against held-out
+/// validation samples once per epoch and keeps the best network seen.
+#[derive(Debug, Clone)]
+pub struct Validator {
+	samples : Vec<Sample>,
+	progress: ValidationProgress,
+}
+
+/// The progress of a validator without its validation samples.
+///
+/// This is kept in checkpoints, so that saving the training state
+/// never copies the validation samples.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct ValidationProgress {
+	interval   : u64,
+	next_check : u64,
+	latest_loss: Option<f64>,
+	best_loss  : f64,
+	best_net   : Option<NeuralNet>,
+	stalls     : u64,
+}
+
+impl Validator {
+	/// Creates a new validator for the given validation samples that
+	/// evaluates every time the given amount of iterations has passed.
+	///
+	/// Returns `None` if there are no validation samples.
+	pub fn new(samples: Vec<Sample>, interval: u64) -> Option<Self> {
+		if samples.is_empty() {
+			return None
+		}
+		let interval = interval.max(1);
+		Some(Validator{
+			samples : samples,
+			progress: ValidationProgress{
+				interval   : interval,
+				next_check : interval,
+				latest_loss: None,
+				best_loss  : f64::INFINITY,
+				best_net   : None,
+				stalls     : 0,
+			}
+		})
+	}
+
+	/// Computes the given loss of the given neural network
+	/// averaged over all validation samples.
+	fn evaluate(&self, net: &mut NeuralNet, loss: Loss) -> f64 {
+		let sum = self.samples
+			.iter()
+			.map(|sample| loss.error(net.feed_forward(sample.input.view()), sample.target.view()))
+			.sum::<f64>();
+		sum / self.samples.len() as f64
+	}
+
+	/// Validates the given neural network if the given iteration
+	/// has reached the next validation check.
+	///
+	/// Keeps a copy of the neural network if its loss is the best so far.
+	pub fn try_validate(&mut self, iterations: u64, net: &mut NeuralNet, loss: Loss) {
+		if iterations < self.progress.next_check {
+			return
+		}
+		let latest   = self.evaluate(net, loss);
+		let progress = &mut self.progress;
+		while progress.next_check <= iterations {
+			progress.next_check += progress.interval;
+		}
+		progress.latest_loss = Some(latest);
+		if latest < progress.best_loss * (1.0 - MIN_IMPROVEMENT) {
+			progress.best_loss = latest;
+			progress.best_net  = Some(net.clone());
+			progress.stalls    = 0;
+		}
+		else {
+			progress.stalls += 1;
+		}
+	}
+
+	/// Returns the number of evaluations in a row that
+	/// have not improved the best validation error.
+	pub fn stalls(&self) -> u64 {
+		self.progress.stalls
+	}
+
+	/// Returns the validation error of the latest evaluation
+	/// or `None` if no evaluation took place, yet.
+	pub fn latest_loss(&self) -> Option<f64> {
+		self.progress.latest_loss
+	}
+
+	/// Returns the validation progress of this validator.
+	pub fn progress(&self) -> &ValidationProgress {
+		&self.progress
+	}
+
+	/// Continues the given validation progress
+	/// with the validation samples of this validator.
+	pub fn resume(&mut self, progress: ValidationProgress) {
+		self.progress = progress
+	}
+
+	/// Consumes this validator and returns the best neural network seen
+	/// or `None` if no evaluation took place.
+	pub fn into_best(self) -> Option<NeuralNet> {
+		self.progress.best_net
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use topology::*;
+	use activation::Activation::Identity;
+
+	fn net() -> NeuralNet {
+		NeuralNet::from_topology(Topology::input(1).output(1, Identity))
+	}
+
+	#[test]
+	fn no_samples() {
+		assert!(Validator::new(vec![], 10).is_none());
+	}
+
+	#[test]
+	fn validates_in_intervals() {
+		let mut validator = Validator::new(vec![Sample::new(vec![0.0], vec![0.0])], 10).unwrap();
+		let mut net = net();
+		validator.try_validate(9, &mut net, Loss::MeanSquaredError);
+		assert!(validator.latest_loss().is_none());
+		validator.try_validate(10, &mut net, Loss::MeanSquaredError);
+		assert!(validator.latest_loss().is_some());
+		assert_eq!(validator.stalls(), 0);
+		validator.try_validate(15, &mut net, Loss::MeanSquaredError);
+		assert_eq!(validator.stalls(), 0);
+		validator.try_validate(25, &mut net, Loss::MeanSquaredError);
+		assert_eq!(validator.stalls(), 1);
+		validator.try_validate(30, &mut net, Loss::MeanSquaredError);
+		assert_eq!(validator.stalls(), 2);
+		assert!(validator.into_best().is_some());
+	}
+
+	#[test]
+	fn resume_progress() {
+		let samples = vec![Sample::new(vec![0.0], vec![0.0])];
+		let mut validator = Validator::new(samples.clone(), 10).unwrap();
+		validator.try_validate(10, &mut net(), Loss::MeanSquaredError);
+		let progress = validator.progress().clone();
+		let mut resumed = Validator::new(samples, 10).unwrap();
+		resumed.resume(progress);
+		assert_eq!(resumed.latest_loss(), validator.latest_loss());
+		resumed.try_validate(20, &mut net(), Loss::MeanSquaredError);
+		assert_eq!(resumed.stalls(), 1);
+	}
+}
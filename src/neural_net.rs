@@ -3,19 +3,35 @@
 
 use std::vec::Vec;
 
-use rand::distributions::Range;
-use ndarray_rand::RandomExt;
+use rand::Rng;
 use ndarray::prelude::*;
 use ndarray::{Zip, Ix};
+use ndarray::linalg::general_mat_mul;
 use itertools::Itertools;
 
-use traits::{LearnRate, LearnMomentum, Predict, UpdateGradients, UpdateWeights};
-use activation::Activation;
+use traits::{
+	LearnRate,
+	LearnMomentum,
+	Predict,
+	PredictBatch,
+	UpdateGradients,
+	UpdateGradientsBatch,
+	UpdateWeights,
+	UpdateWeightsBatch,
+	SetOptimizer
+};
+use activation::{self, Activation, OutputActivation};
+use loss::Loss;
+use regularization::Regularization;
+use clipping::GradientClipping;
+use optimizer::{Optimizer, OptimizerState};
 use topology::*;
+use scaling::{Scaling, SampleScaling};
+use rng;
 
 /// A fully connected layer within a neural net.
 ///
-/// The layer constists of a weights and a delta-weights matrix with equal dimensions
+/// The layer constists of a weights and a weight-gradients matrix with equal dimensions
 /// and an output and gradients vector of equal size.
 ///
 /// The values stored within the n-th column of the two weights matrices are respective to
@@ -27,8 +43,23 @@ use topology::*;
 ///
 /// For predicting only the weights matrix and the outputs vector is required.
 ///
-/// The outputs and gradients vectors are just used as frequently used intermediate buffers
-/// which should speed up computation.
+/// The outputs, gradients and weight-gradients are just used as frequently used intermediate
+/// buffers which should speed up computation.
+///
+/// The optimizer state holds everything the optimizer needs to remember between weight
+/// updates, e.g. the previous delta weights for momentum based gradient descent.
+///
+/// Layers with a dropout rate randomly drop out their outputs while training.
+///
+/// The regularization of the weights is configured for every training
+/// session and is not serialized.
+///
+/// The weight gradients of all layers are computed before any weights are
+/// updated so that the neural net can clip them all together.
+///
+/// When learning from batches of samples the batch outputs and batch gradients matrices
+/// store the outputs and gradients for all samples of the batch, one sample per row.
+/// They are (re-)allocated whenever the batch size changes and are not serialized.
 ///
 /// The data structure is organized in a way that it mainly represents the connections
 /// between two adjacent neuron layers.
@@ -41,11 +72,98 @@ use topology::*;
 #[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 struct FullyConnectedLayer {
-	weights      : Array2<f32>,
-	delta_weights: Array2<f32>,
-	outputs      : Array1<f32>,
-	gradients    : Array1<f32>,
-	activation   : Activation,
+	weights         : Array2<f32>,
+	weight_gradients: Array2<f32>,
+	optimizer       : OptimizerState,
+	outputs         : Array1<f32>,
+	gradients       : Array1<f32>,
+	activation      : Activation,
+	softmax         : bool,
+	dropout         : Dropout,
+
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	regularization  : Regularization,
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	batch_outputs   : Array2<f32>,
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	batch_gradients : Array2<f32>,
+}
+
+/// The dropout of the outputs of a layer during training.
+///
+/// Kept outputs are scaled up by the inverse of the keep probability
+/// so that predictions need no scaling at all (inverted dropout).
+///
+/// The masks store zero for dropped and the scale for kept outputs.
+/// They are drawn anew for every training iteration and are not serialized.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+struct Dropout {
+	rate      : f32,
+
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	mask      : Array1<f32>,
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	batch_mask: Array2<f32>,
+}
+
+impl Dropout {
+	/// Creates a new dropout with the given rate.
+	fn new(rate: f32) -> Self {
+		Dropout{
+			rate      : rate,
+			mask      : Array1::zeros(0),
+			batch_mask: Array2::zeros((0, 0)),
+		}
+	}
+
+	/// Returns `true` if outputs are dropped out at all.
+	fn is_active(&self) -> bool {
+		self.rate > 0.0
+	}
+
+	/// Returns the mask value of a single output, i.e. zero
+	/// with the probability of the rate or else the scale.
+	fn draw<R: Rng>(rate: f32, rng: &mut R) -> f32 {
+		if rng.gen::<f32>() < rate { 0.0 } else { 1.0 / (1.0 - rate) }
+	}
+
+	/// Draws a new mask and applies it onto the given outputs.
+	fn apply<R: Rng>(&mut self, mut outputs: ArrayViewMut1<f32>, rng: &mut R) {
+		if !self.is_active() {
+			return
+		}
+		if self.mask.len() != outputs.len() {
+			self.mask = Array1::zeros(outputs.len());
+		}
+		let rate = self.rate;
+		for (mask, output) in izip!(self.mask.iter_mut(), outputs.iter_mut()) {
+			*mask    = Dropout::draw(rate, rng);
+			*output *= *mask;
+		}
+	}
+
+	/// Batch version of `apply` with one output per row.
+	fn apply_batch<R: Rng>(&mut self, mut outputs: ArrayViewMut2<f32>, rng: &mut R) {
+		if !self.is_active() {
+			return
+		}
+		if self.batch_mask.dim() != outputs.dim() {
+			self.batch_mask = Array2::zeros(outputs.dim());
+		}
+		let rate = self.rate;
+		Zip::from(&mut self.batch_mask).and(&mut outputs).apply(|mask, output| {
+			*mask    = Dropout::draw(rate, rng);
+			*output *= *mask;
+		});
+	}
+
+	/// Returns the derivative of the given activation function for a dropped
+	/// out output with the given mask, i.e. the derivative of the original output
+	/// scaled like the output itself.
+	fn derived(act: Activation, output: f32, mask: f32) -> f32 {
+		if mask == 0.0 { 0.0 } else { mask * act.derived(output / mask) }
+	}
 }
 
 /// A neural net.
@@ -58,11 +176,21 @@ struct FullyConnectedLayer {
 /// For example when the user uses ```predict``` from ```NeuralNet``` this
 /// object organizes the input data throughout all of its owned layers and pipes
 /// the result in the last layer back to the user.
+///
+/// Neural nets that were trained with scaled samples scale their inputs
+/// and unscale their outputs in ```predict``` with the fitted scalings.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct NeuralNet {
 	/// the layers within this ```NeuralNet```
 	layers: Vec<FullyConnectedLayer>,
+
+	/// the scalings of inputs and outputs fitted to the training samples
+	scaling: SampleScaling,
+
+	/// the clipping of the weight gradients configured for a training session
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	clipping: GradientClipping,
 }
 
 impl FullyConnectedLayer {
@@ -83,9 +211,11 @@ impl FullyConnectedLayer {
 		FullyConnectedLayer{
 			weights,
 
-			// Must be initialized with zeros or else computation
-			// in the first iteration will be screwed!
-			delta_weights: Array2::zeros(biased_shape),
+			// Fully overwritten before every use.
+			weight_gradients: Array2::zeros(biased_shape),
+
+			// Momentum based gradient descent until told otherwise.
+			optimizer: OptimizerState::new(Optimizer::default(), biased_shape),
 
 			// Construct outputs with a `1.0` constant bias value as last element.
 			outputs: Array1::from_iter(iter::repeat(0.0).take(n_outputs)),
@@ -96,26 +226,42 @@ impl FullyConnectedLayer {
 
 			// Initialize the activation function. TODO: Should be moved into its own layer.
 			activation: activation,
+
+			// Outputs are not normalized until told otherwise.
+			softmax: false,
+
+			// Outputs are never dropped out until told otherwise.
+			dropout: Dropout::default(),
+
+			// No regularization until told otherwise.
+			regularization: Regularization::default(),
+
+			// Allocated on demand for the actual batch size.
+			batch_outputs  : Array2::zeros((0, n_outputs)),
+			batch_gradients: Array2::zeros((0, n_outputs)),
 		}
 	}
 
-	/// Creates a FullyConnectedLayer with randomized weights.
+	/// Creates a FullyConnectedLayer with weights drawn from the initializers of the given layer.
 	///
 	/// Implicitely creates weights for the bias neuron,
 	/// so the dimensions of the weights matrix is equal to
 	/// (output)x(input+1).
-	///
-	/// The weights are randomized within the open interval (0,1).
-	/// This excludes 0.0 and 1.0 as weights.
-	/// Other optional intervals may come with a future update!
-	fn random(n_inputs: Ix, n_outputs: Ix, activation: Activation) -> Self {
+	fn random<R: Rng>(layer: &Layer, rng: &mut R) -> Self {
+		let (n_inputs, n_outputs) = (layer.inputs, layer.outputs);
 		assert!(n_inputs >= 1 && n_outputs >= 1);
 
 		let biased_inputs = n_inputs  + 1;
 		let biased_shape  = (n_outputs, biased_inputs);
 
-		FullyConnectedLayer::with_weights(
-			Array2::random(biased_shape, Range::new(-1.0, 1.0)), activation)
+		let weights = Array2::from_shape_fn(biased_shape, |(_, input)| {
+			let initializer = if input == n_inputs { &layer.bias_initializer } else { &layer.initializer };
+			initializer.sample(n_inputs, n_outputs, rng)
+		});
+		let mut result = FullyConnectedLayer::with_weights(weights, layer.activation);
+		result.dropout = Dropout::new(layer.dropout);
+		result.softmax = layer.softmax;
+		result
 	}
 
 	/// Count output neurons of this layer.
@@ -175,16 +321,74 @@ impl FullyConnectedLayer {
 			let s   = weights.len();
 			*output = act.base(weights.slice(s![..-1]).dot(&input) + weights[s-1]);
 		});
+		if self.softmax {
+			activation::softmax(self.outputs.view_mut());
+		}
 		// general_matvec_mul(&mut self.outputs, &self.weights, &input);
 		// =================================================================================
 
 		self.output_view() // required for folding the general operation
 	}
 
+	/// Takes a batch of inputs, one input per row, and performs a feed forward
+	/// procedure for all of them at once using a matrix-matrix multiplication.
+	/// Outputs of this operation will be stored within this layer, one output per row,
+	/// and be returned as readable view.
+	fn feed_forward_batch(&mut self,
+	                      inputs: ArrayView2<f32>)
+	                      -> ArrayView2<f32> {
+		debug_assert_eq!(self.weights.cols(), inputs.cols() + 1);
+
+		let batch_size = inputs.rows();
+		if self.batch_outputs.rows() != batch_size {
+			self.batch_outputs   = Array2::zeros((batch_size, self.count_outputs()));
+			self.batch_gradients = Array2::zeros((batch_size, self.count_outputs()));
+		}
+
+		let act    = self.activation; // required because of non-lexical borrows
+		let n_cols = self.weights.cols();
+
+		let unbiased_weights = self.weights.slice(s![.., ..-1]);
+		general_mat_mul(1.0, &inputs, &unbiased_weights.t(), 0.0, &mut self.batch_outputs);
+		self.batch_outputs += &self.weights.column(n_cols - 1);
+		self.batch_outputs.mapv_inplace(|output| act.base(output));
+		if self.softmax {
+			for row in self.batch_outputs.genrows_mut() {
+				activation::softmax(row);
+			}
+		}
+
+		self.batch_outputs.view()
+	}
+
+	/// Performs a feed forward procedure like `feed_forward` while training
+	/// and then randomly drops out outputs if this layer has a dropout rate.
+	fn feed_forward_dropout<R: Rng>(&mut self,
+	                                input: ArrayView1<f32>,
+	                                rng  : &mut R)
+	                                -> ArrayView1<f32> {
+		self.feed_forward(input);
+		self.dropout.apply(self.outputs.view_mut(), rng);
+		self.output_view()
+	}
+
+	/// Batch version of `feed_forward_dropout` with one input per row.
+	fn feed_forward_batch_dropout<R: Rng>(&mut self,
+	                                      inputs: ArrayView2<f32>,
+	                                      rng   : &mut R)
+	                                      -> ArrayView2<f32> {
+		self.feed_forward_batch(inputs);
+		self.dropout.apply_batch(self.batch_outputs.view_mut(), rng);
+		self.batch_outputs.view()
+	}
+
 	/// Used internally in the output layer to initialize gradients for the back propagation phase.
 	/// Sets the gradient for the bias neuron to zero - hopefully this is the correct behaviour.
+	///
+	/// The gradients are derived from the given loss function.
 	fn calculate_output_gradients(&mut self,
-	                              target_values: ArrayView1<f32>)
+	                              target_values: ArrayView1<f32>,
+	                              loss: Loss)
 	                              -> &Self {
 		debug_assert_eq!(self.count_outputs()  , target_values.len());
 		debug_assert_eq!(self.count_gradients(), target_values.len() + 1); // no calculation for bias!
@@ -196,7 +400,7 @@ impl FullyConnectedLayer {
 				.and(&target_values)
 				.and(&self.outputs)
 				.apply(|gradient, &target, &output| {
-			*gradient = (target - output) * act.derived(output)
+			*gradient = loss.gradient(act, target, output)
 		});
 
 		// Old version of the new Zip mechanics above
@@ -207,6 +411,27 @@ impl FullyConnectedLayer {
 		self
 	}
 
+	/// Batch version of `calculate_output_gradients` with one target per row.
+	///
+	/// Batch gradients have no entries for the bias neuron since they are never used.
+	fn calculate_output_gradients_batch(&mut self,
+	                                    target_values: ArrayView2<f32>,
+	                                    loss: Loss)
+	                                    -> &Self {
+		debug_assert_eq!(self.batch_outputs.dim(), target_values.dim());
+
+		let act = self.activation; // required because of non-lexical borrows
+
+		Zip::from(&mut self.batch_gradients)
+				.and(&target_values)
+				.and(&self.batch_outputs)
+				.apply(|gradient, &target, &output| {
+			*gradient = loss.gradient(act, target, output)
+		});
+
+		self
+	}
+
 	/// Sets all gradient values in this layer to zero.
 	/// This is required as initialization step before propagating gradients
 	/// for the efficient implementation of this library.
@@ -222,8 +447,16 @@ impl FullyConnectedLayer {
 
 		let act = self.activation; // required because of non-lexical borrows
 		use std::iter;
-		izip!(self.gradients.iter_mut(), self.outputs.iter().chain(iter::once(&1.0)))
-			.foreach(|(gradient, &output)| *gradient *= act.derived(output));
+		if self.dropout.is_active() {
+			izip!(self.gradients.iter_mut(),
+			      self.outputs.iter().chain(iter::once(&1.0)),
+			      self.dropout.mask.iter().chain(iter::once(&1.0)))
+				.foreach(|(gradient, &output, &mask)| *gradient *= Dropout::derived(act, output, mask));
+		}
+		else {
+			izip!(self.gradients.iter_mut(), self.outputs.iter().chain(iter::once(&1.0)))
+				.foreach(|(gradient, &output)| *gradient *= act.derived(output));
+		}
 	}
 
 	/// Back propagate gradients from the previous layer (in reversed order) to this layer
@@ -246,57 +479,111 @@ impl FullyConnectedLayer {
 		self // for chaining in a fold expression
 	}
 
-	/// Updates the connection weights of this layer.
+	/// Batch version of `propagate_gradients` operating on all rows at once.
+	fn propagate_gradients_batch(&mut self,
+	                             prev: &FullyConnectedLayer)
+	                             -> &Self {
+		debug_assert_eq!(prev.weights.cols(), self.count_outputs() + 1);
+		debug_assert_eq!(prev.batch_gradients.rows(), self.batch_gradients.rows());
+
+		let prev_unbiased_weights = prev.weights.slice(s![.., ..-1]);
+		general_mat_mul(1.0, &prev.batch_gradients, &prev_unbiased_weights, 0.0, &mut self.batch_gradients);
+
+		let act = self.activation; // required because of non-lexical borrows
+		if self.dropout.is_active() {
+			Zip::from(&mut self.batch_gradients)
+				.and(&self.batch_outputs)
+				.and(&self.dropout.batch_mask)
+				.apply(|gradient, &output, &mask| *gradient *= Dropout::derived(act, output, mask));
+		}
+		else {
+			Zip::from(&mut self.batch_gradients)
+				.and(&self.batch_outputs)
+				.apply(|gradient, &output| *gradient *= act.derived(output));
+		}
+
+		self // for chaining in a fold expression
+	}
+
+	/// Computes the weight gradients of this layer including the regularization penalty.
 	/// This operation is usually used after successful computation of gradients.
-	fn update_weights(&mut self,
-	                  prev_outputs: ArrayView1<f32>,
-	                  learn_rate  : LearnRate,
-	                  learn_mom   : LearnMomentum)
-	                  -> ArrayView1<f32> {
+	/// Returns readable reference to the outputs of this layer to allow chaining.
+	fn calculate_weight_gradients(&mut self,
+	                              prev_outputs: ArrayView1<f32>)
+	                              -> ArrayView1<f32> {
 		debug_assert_eq!(prev_outputs.len() + 1, self.weights.cols());
 		debug_assert_eq!(self.count_gradients(), self.weights.rows() + 1);
 
 		use std::iter;
 
-		// ==================================================================== //
-		// OLD
-		// ==================================================================== //
-		izip!(self.weights.genrows_mut(),
-		      self.delta_weights.genrows_mut(),
-		      self.gradients.iter())
-			.foreach(|(mut weights_row, mut delta_weights_row, gradient)| {
-				izip!(prev_outputs.iter().chain(iter::once(&1.0)), delta_weights_row.iter_mut())
-					.foreach(|(prev_output, delta_weight)| {
-						*delta_weight =
-							// Individual input, magnified by the gradient and train rate
-							learn_rate.0 * prev_output * gradient
-							// Also add momentum which is a fraction of the previous delta weight
-							+ learn_mom.0 * *delta_weight;
+		// Each weight gradient is the individual input magnified by the gradient.
+		izip!(self.weight_gradients.genrows_mut(), self.gradients.iter())
+			.foreach(|(mut weight_gradients_row, gradient)| {
+				izip!(weight_gradients_row.iter_mut(), prev_outputs.iter().chain(iter::once(&1.0)))
+					.foreach(|(weight_gradient, prev_output)| {
+						*weight_gradient = prev_output * gradient;
 					});
-				weights_row += &delta_weights_row;
 			});
-		// ==================================================================== //
-		// NEW
-		// ==================================================================== //
-		// multizip((self.delta_weights.genrows_mut(),
-		//           self.gradients.iter()))
-		// 	.foreach(|(mut delta_weights_row, gradient)| {
-		// 		multizip((prev_outputs.iter().chain(iter::once(&1.0)),
-		// 		          delta_weights_row.iter_mut()))
-		// 			.foreach(|(prev_output, delta_weight)| {
-		// 				*delta_weight =
-		// 					// Individual input, magnified by the gradient and train rate
-		// 					learn_rate.0 * prev_output * gradient
-		// 					// Also add momentum which is a fraction of the previous delta weight
-		// 					+ learn_mom.0 * *delta_weight;
-		// 			});
-		// 	});
-		// self.weights += &self.delta_weights;
-		// ==================================================================== //
+
+		self.regularization.apply_penalty(self.weights.view(), self.weight_gradients.view_mut());
 
 		self.reset_gradients();
 		self.output_view()
 	}
+
+	/// Batch version of `calculate_weight_gradients` that averages
+	/// the weight gradients of all samples within the batch.
+	fn calculate_weight_gradients_batch(&mut self,
+	                                    prev_outputs: ArrayView2<f32>)
+	                                    -> ArrayView2<f32> {
+		debug_assert_eq!(prev_outputs.cols() + 1, self.weights.cols());
+		debug_assert_eq!(prev_outputs.rows(), self.batch_gradients.rows());
+
+		let scale  = 1.0 / prev_outputs.rows() as f32;
+		let n_cols = self.weight_gradients.cols();
+
+		general_mat_mul(scale, &self.batch_gradients.t(), &prev_outputs,
+		                0.0, &mut self.weight_gradients.slice_mut(s![.., ..-1]));
+		Zip::from(self.weight_gradients.column_mut(n_cols - 1))
+			.and(self.batch_gradients.gencolumns())
+			.apply(|bias_gradient, gradients| *bias_gradient = gradients.scalar_sum() * scale);
+
+		self.regularization.apply_penalty(self.weights.view(), self.weight_gradients.view_mut());
+
+		self.batch_outputs.view()
+	}
+
+	/// Updates the connection weights of this layer with its weight gradients.
+	fn apply_weight_gradients(&mut self, learn_rate: LearnRate, learn_mom: LearnMomentum) {
+		self.optimizer.update(
+			&mut self.weights, self.weight_gradients.view(), learn_rate, learn_mom);
+		self.regularization.apply_decay(self.weights.view_mut(), learn_rate);
+	}
+
+	/// Returns `true` if any output, gradient or weight of this layer is not finite.
+	fn has_non_finite(&self) -> bool {
+		let non_finite = |value: &f32| !value.is_finite();
+		self.outputs.iter().any(non_finite) ||
+		self.batch_outputs.iter().any(non_finite) ||
+		self.batch_gradients.iter().any(non_finite) ||
+		self.weight_gradients.iter().any(non_finite) ||
+		self.weights.iter().any(non_finite)
+	}
+
+	/// Switches the optimizer of this layer to the given one.
+	///
+	/// The state of the current optimizer is kept if it already is the given one.
+	fn set_optimizer(&mut self, optimizer: Optimizer) {
+		if self.optimizer.optimizer() != optimizer {
+			self.reset_optimizer(optimizer)
+		}
+	}
+
+	/// Switches the optimizer of this layer to the given one
+	/// and discards all state of the current optimizer.
+	fn reset_optimizer(&mut self, optimizer: Optimizer) {
+		self.optimizer = OptimizerState::new(optimizer, self.weights.dim());
+	}
 }
 
 impl NeuralNet {
@@ -305,47 +592,215 @@ impl NeuralNet {
 	/// This constructor should only be used internally!
 	fn from_vec(layers: Vec<FullyConnectedLayer>) -> Self {
 		NeuralNet {
-			layers: layers
+			layers  : layers,
+			scaling : SampleScaling::default(),
+			clipping: GradientClipping::default()
 		}
 	}
 
 	/// Creates a new neural network of fully connected layers from a given topology.
 	pub fn from_topology(topology: Topology) -> Self {
+		NeuralNet::from_topology_using(topology, &mut rng::new(None))
+	}
+
+	/// Creates a new neural network of fully connected layers from a given topology
+	/// with weights drawn from the given seed.
+	///
+	/// The same topology and seed always result in the same neural network.
+	pub fn from_topology_seeded(topology: Topology, seed: u64) -> Self {
+		NeuralNet::from_topology_using(topology, &mut rng::from_seed(seed))
+	}
+
+	/// Creates a new neural network of fully connected layers from a given topology
+	/// with weights drawn from the given random number generator.
+	pub(crate) fn from_topology_using<R: Rng>(topology: Topology, rng: &mut R) -> Self {
 		NeuralNet::from_vec(topology
 			.iter_layers()
-			.map(|&layer| {
-				FullyConnectedLayer::random(
-					layer.inputs, layer.outputs, layer.activation)
-			})
+			.map(|layer| FullyConnectedLayer::random(layer, rng))
 			.collect()
 		)
 	}
-}
 
-impl<'b, A> Predict<A> for NeuralNet
-	where A: Into<ArrayView1<'b, f32>>
-{
-	fn predict(&mut self, input: A) -> ArrayView1<f32> {
-		let input  = input.into();
+	/// Returns the number of input neurons.
+	pub fn len_input(&self) -> usize {
+		self.layers
+			.first()
+			.expect("a neural net must have a valid first layer!")
+			.weights
+			.cols() - 1
+	}
+
+	/// Returns the number of output neurons.
+	pub fn len_output(&self) -> usize {
+		self.layers
+			.last()
+			.expect("a neural net must have a valid last layer!")
+			.weights
+			.rows()
+	}
+
+	/// Returns the activation function of the output layer.
+	pub fn output_activation(&self) -> OutputActivation {
+		let layer = self.layers
+			.last()
+			.expect("a neural net must have a valid last layer!");
+		if layer.softmax { OutputActivation::SoftMax } else { layer.activation.into() }
+	}
+
+	/// Returns the optimizer currently used to update the weights.
+	pub fn optimizer(&self) -> Optimizer {
+		self.layers
+			.first()
+			.expect("a neural net must have a valid first layer!")
+			.optimizer
+			.optimizer()
+	}
+
+	/// Returns the scaling applied to inputs before they are fed forward if any.
+	pub fn input_scaling(&self) -> Option<&Scaling> {
+		self.scaling.inputs.as_ref()
+	}
+
+	/// Returns the scaling whose inverse is applied to outputs of predictions if any.
+	///
+	/// This is the scaling of the targets of the training samples.
+	pub fn output_scaling(&self) -> Option<&Scaling> {
+		self.scaling.targets.as_ref()
+	}
+
+	/// Returns the scalings of the samples this neural network learns from.
+	pub(crate) fn scaling(&self) -> &SampleScaling {
+		&self.scaling
+	}
+
+	/// Sets the scalings of the samples this neural network learns from.
+	pub(crate) fn set_scaling(&mut self, scaling: SampleScaling) {
+		self.scaling = scaling;
+	}
+
+	/// Returns the number of layers excluding the input layer.
+	pub fn len_layers(&self) -> usize {
+		self.layers.len()
+	}
+
+	/// Sets the regularization of every layer to the respective given one.
+	pub(crate) fn set_regularization(&mut self, regularization: &[Regularization]) {
+		debug_assert_eq!(regularization.len(), self.layers.len());
+		for (layer, &regularization) in self.layers.iter_mut().zip(regularization) {
+			layer.regularization = regularization;
+		}
+	}
+
+	/// Clips the weight gradients of all layers with the given gradient clipping
+	/// from now on.
+	pub(crate) fn set_clipping(&mut self, clipping: GradientClipping) {
+		self.clipping = clipping;
+	}
+
+	/// Clips the weight gradients of all layers and then updates
+	/// the weights of all layers with them.
+	fn apply_weight_gradients(&mut self, rate: LearnRate, momentum: LearnMomentum) {
+		if self.clipping != GradientClipping::None {
+			let mut gradients = self.layers
+				.iter_mut()
+				.map(|layer| layer.weight_gradients.view_mut())
+				.collect::<Vec<_>>();
+			self.clipping.clip(&mut gradients);
+		}
+		for layer in &mut self.layers {
+			layer.apply_weight_gradients(rate, momentum);
+		}
+	}
+
+	/// Returns the index of the first layer with any output, gradient or weight
+	/// that is not finite or `None` if all of them are finite.
+	pub(crate) fn find_non_finite_layer(&self) -> Option<usize> {
+		self.layers.iter().position(FullyConnectedLayer::has_non_finite)
+	}
+
+	/// Returns `true` if any layer drops out outputs while training.
+	pub(crate) fn has_dropout(&self) -> bool {
+		self.layers.iter().any(|layer| layer.dropout.is_active())
+	}
+
+	/// Feeds the given already scaled input forward through all layers while training
+	/// and randomly drops out outputs of layers with a dropout rate.
+	pub(crate) fn feed_forward_dropout<R: Rng>(&mut self, input: ArrayView1<f32>, rng: &mut R) -> ArrayView1<f32> {
+		if let Some((first, tail)) = self.layers.split_first_mut() {
+			tail.iter_mut()
+				.fold(first.feed_forward_dropout(input, rng),
+				      |prev, layer| layer.feed_forward_dropout(prev, rng))
+		} else {
+			panic!("A Neural Net is guaranteed to have at least one layer so this situation \
+			        should never happen!");
+		}
+	}
+
+	/// Batch version of `feed_forward_dropout` with one input per row.
+	pub(crate) fn feed_forward_batch_dropout<R: Rng>(&mut self, inputs: ArrayView2<f32>, rng: &mut R) -> ArrayView2<f32> {
 		if let Some((first, tail)) = self.layers.split_first_mut() {
 			tail.iter_mut()
-				.fold(first.feed_forward(input),
-				      |prev, layer| layer.feed_forward(prev))
+				.fold(first.feed_forward_batch_dropout(inputs, rng),
+				      |prev, layer| layer.feed_forward_batch_dropout(prev, rng))
 		} else {
 			panic!("A Neural Net is guaranteed to have at least one layer so this situation \
 			        should never happen!");
 		}
 	}
+
+	/// Returns the regularization penalty of all weights that is added to the loss.
+	pub(crate) fn penalty(&self) -> f64 {
+		self.layers
+			.iter()
+			.map(|layer| layer.regularization.penalty(layer.weights.view()))
+			.sum()
+	}
+
+	/// Feeds the given already scaled input forward through all layers
+	/// and returns the outputs of the last layer without unscaling them.
+	pub(crate) fn feed_forward(&mut self, input: ArrayView1<f32>) -> ArrayView1<f32> {
+		feed_forward(&mut self.layers, input)
+	}
+}
+
+/// Feeds the given input forward through all of the given layers
+/// and returns the outputs of the last layer.
+fn feed_forward<'a>(layers: &'a mut [FullyConnectedLayer], input: ArrayView1<f32>) -> ArrayView1<'a, f32> {
+	if let Some((first, tail)) = layers.split_first_mut() {
+		tail.iter_mut()
+			.fold(first.feed_forward(input),
+			      |prev, layer| layer.feed_forward(prev))
+	} else {
+		panic!("A Neural Net is guaranteed to have at least one layer so this situation \
+		        should never happen!");
+	}
+}
+
+impl<'b, A> Predict<A> for NeuralNet
+	where A: Into<ArrayView1<'b, f32>>
+{
+	fn predict(&mut self, input: A) -> ArrayView1<f32> {
+		let input = input.into();
+		let input = match self.scaling.inputs {
+			Some(ref mut scaling) => scaling.scaled(input),
+			None                  => input.view()
+		};
+		let output = feed_forward(&mut self.layers, input);
+		match self.scaling.targets {
+			Some(ref mut scaling) => scaling.unscaled(output),
+			None                  => output
+		}
+	}
 }
 
 impl<'a, A> UpdateGradients<A> for NeuralNet
 	where A: Into<ArrayView1<'a, f32>>
 {
-	fn update_gradients(&mut self, target_values: A) {
+	fn update_gradients(&mut self, target_values: A, loss: Loss) {
 		if let Some((&mut ref mut last, ref mut tail)) = self.layers.split_last_mut() {
 			tail.iter_mut()
 				.rev()
-				.fold(last.calculate_output_gradients(target_values.into()),
+				.fold(last.calculate_output_gradients(target_values.into(), loss),
 				      |prev, layer| layer.propagate_gradients(prev));
 		}
 	}
@@ -358,8 +813,66 @@ impl<'b, A> UpdateWeights<A> for NeuralNet
 		let input = input.into();
 		if let Some((first, tail)) = self.layers.split_first_mut() {
 			tail.iter_mut()
-				.fold(first.update_weights(input, rate, momentum),
-				      |prev, layer| layer.update_weights(prev, rate, momentum));
+				.fold(first.calculate_weight_gradients(input),
+				      |prev, layer| layer.calculate_weight_gradients(prev));
+		}
+		self.apply_weight_gradients(rate, momentum);
+	}
+}
+
+impl<'b, A> PredictBatch<A> for NeuralNet
+	where A: Into<ArrayView2<'b, f32>>
+{
+	fn predict_batch(&mut self, inputs: A) -> ArrayView2<f32> {
+		let inputs = inputs.into();
+		if let Some((first, tail)) = self.layers.split_first_mut() {
+			tail.iter_mut()
+				.fold(first.feed_forward_batch(inputs),
+				      |prev, layer| layer.feed_forward_batch(prev))
+		} else {
+			panic!("A Neural Net is guaranteed to have at least one layer so this situation \
+			        should never happen!");
+		}
+	}
+}
+
+impl<'a, A> UpdateGradientsBatch<A> for NeuralNet
+	where A: Into<ArrayView2<'a, f32>>
+{
+	fn update_gradients_batch(&mut self, target_values: A, loss: Loss) {
+		if let Some((&mut ref mut last, ref mut tail)) = self.layers.split_last_mut() {
+			tail.iter_mut()
+				.rev()
+				.fold(last.calculate_output_gradients_batch(target_values.into(), loss),
+				      |prev, layer| layer.propagate_gradients_batch(prev));
+		}
+	}
+}
+
+impl<'b, A> UpdateWeightsBatch<A> for NeuralNet
+	where A: Into<ArrayView2<'b, f32>>
+{
+	fn update_weights_batch(&mut self, inputs: A, rate: LearnRate, momentum: LearnMomentum) {
+		let inputs = inputs.into();
+		if let Some((first, tail)) = self.layers.split_first_mut() {
+			tail.iter_mut()
+				.fold(first.calculate_weight_gradients_batch(inputs),
+				      |prev, layer| layer.calculate_weight_gradients_batch(prev));
+		}
+		self.apply_weight_gradients(rate, momentum);
+	}
+}
+
+impl SetOptimizer for NeuralNet {
+	fn set_optimizer(&mut self, optimizer: Optimizer) {
+		for layer in &mut self.layers {
+			layer.set_optimizer(optimizer.clone());
+		}
+	}
+
+	fn reset_optimizer(&mut self, optimizer: Optimizer) {
+		for layer in &mut self.layers {
+			layer.reset_optimizer(optimizer.clone());
 		}
 	}
 }
@@ -367,6 +880,7 @@ impl<'b, A> UpdateWeights<A> for NeuralNet
 #[cfg(test)]
 mod tests {
 	pub use super::*;
+	use initializer::Initializer;
 
 	mod fully_connected_layer {
 		use super::*;
@@ -379,7 +893,7 @@ mod tests {
 			let weights = Array1::linspace(1.0, 12.0, 12).into_shape((3, 4)).unwrap();
 			let layer = FullyConnectedLayer::with_weights(weights.clone(), Identity);
 			assert_eq!(layer.weights, weights);
-			assert_eq!(layer.delta_weights, Array::zeros((3, 4)));
+			assert_eq!(layer.optimizer, OptimizerState::new(Optimizer::Momentum, (3, 4)));
 			assert_eq!(layer.gradients, Array1::zeros(4));
 			let expected_outputs = Array1::from_iter(iter::repeat(0.0).take(3));
 			assert_eq!(layer.outputs, expected_outputs);
@@ -415,7 +929,7 @@ mod tests {
 			assert_eq!(gradients, expected_gradients);
 			assert_eq!(outputs  , expected_outputs);
 			assert_eq!(gradients, Array1::zeros(4));
-			layer.calculate_output_gradients(expected.view()).to_owned();
+			layer.calculate_output_gradients(expected.view(), Loss::MeanSquaredError);
 			let targets   = Array1::from_vec(vec![1.0, 2.0, 3.0, 0.0]);
 			let gradients = layer.gradients_view().to_owned();
 
@@ -432,11 +946,17 @@ mod tests {
 			use self::Activation::{Identity};
 
 			let fst_layer = FullyConnectedLayer{
-				weights      : Array1::linspace(1.0, 12.0, 12).into_shape((3, 4)).unwrap(),
-				delta_weights: Array::zeros((3, 4)),
-				outputs      : Array1::from_iter(iter::repeat(0.0).take(3)),
-				gradients    : Array1::linspace(10.0, 40.0, 4),
-				activation   : Identity
+				weights         : Array1::linspace(1.0, 12.0, 12).into_shape((3, 4)).unwrap(),
+				weight_gradients: Array::zeros((3, 4)),
+				optimizer       : OptimizerState::new(Optimizer::Momentum, (3, 4)),
+				outputs         : Array1::from_iter(iter::repeat(0.0).take(3)),
+				gradients       : Array1::linspace(10.0, 40.0, 4),
+				activation      : Identity,
+				softmax         : false,
+				dropout         : Dropout::default(),
+				regularization  : Regularization::default(),
+				batch_outputs   : Array2::zeros((0, 3)),
+				batch_gradients : Array2::zeros((0, 3))
 			};
 
 			let mut snd_layer = FullyConnectedLayer::with_weights(
@@ -460,13 +980,20 @@ mod tests {
 			let lm = LearnMomentum(1.0);
 			let outputs = Array1::from_iter(iter::repeat(0.0).take(3));
 			let mut layer = FullyConnectedLayer{
-				weights      : Array1::linspace(1.0, 12.0, 12).into_shape((3, 4)).unwrap(),
-				delta_weights: Array::zeros((3, 4)),
-				outputs      : Array1::from_iter(iter::repeat(0.0).take(3)),
-				gradients    : Array1::linspace(10.0, 40.0, 4),
-				activation   : Identity
+				weights         : Array1::linspace(1.0, 12.0, 12).into_shape((3, 4)).unwrap(),
+				weight_gradients: Array::zeros((3, 4)),
+				optimizer       : OptimizerState::new(Optimizer::Momentum, (3, 4)),
+				outputs         : Array1::from_iter(iter::repeat(0.0).take(3)),
+				gradients       : Array1::linspace(10.0, 40.0, 4),
+				activation      : Identity,
+				softmax         : false,
+				dropout         : Dropout::default(),
+				regularization  : Regularization::default(),
+				batch_outputs   : Array2::zeros((0, 3)),
+				batch_gradients : Array2::zeros((0, 3))
 			};
-			let result_outputs = layer.update_weights(outputs.view(), lr, lm).to_owned();
+			let result_outputs = layer.calculate_weight_gradients(outputs.view()).to_owned();
+			layer.apply_weight_gradients(lr, lm);
 			let target_outputs = Array::from_vec(vec![0.0, 0.0, 0.0]);
 			let result_weights = layer.weights.clone();
 			let target_weights = Array::from_vec(vec![
@@ -478,6 +1005,126 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn sizes_and_optimizer() {
+		use self::Activation::{Tanh, Identity};
+		let mut net = NeuralNet::from_topology(
+			Topology::input(3).layer(4, Tanh).output(2, Identity));
+		assert_eq!(net.len_input(), 3);
+		assert_eq!(net.len_output(), 2);
+		assert_eq!(net.optimizer(), Optimizer::Momentum);
+		net.set_optimizer(Optimizer::adam());
+		assert_eq!(net.optimizer(), Optimizer::adam());
+	}
+
+	#[test]
+	fn initialized_weights() {
+		use self::Activation::{ReLU, Tanh};
+		let net = NeuralNet::from_topology_seeded(Topology::input(3)
+			.layer(4, ReLU)
+			.output(2, Tanh)
+			.initializer(Initializer::custom(|fan_in, fan_out| (fan_in + fan_out) as f32))
+			.bias_initializer(Initializer::Uniform(1.0, 2.0)), 42);
+		let (hidden, output) = (&net.layers[0].weights, &net.layers[1].weights);
+		let limit = (6.0f32 / 3.0).sqrt();
+		assert!(hidden.slice(s![.., ..-1]).iter().all(|w| w.abs() <= limit && *w != 0.0));
+		assert!(hidden.slice(s![.., -1..]).iter().all(|&w| w == 0.0));
+		assert!(output.slice(s![.., ..-1]).iter().all(|&w| w == 6.0));
+		assert!(output.slice(s![.., -1..]).iter().all(|&w| (1.0..2.0).contains(&w)));
+	}
+
+	#[test]
+	fn predict_batch_matches_predict() {
+		use self::Activation::{Tanh, Identity};
+		let mut net = NeuralNet::from_vec(vec![
+			FullyConnectedLayer::with_weights(
+				Array1::linspace(-1.0, 1.0, 6).into_shape((2, 3)).unwrap(), Tanh),
+			FullyConnectedLayer::with_weights(
+				Array1::linspace(0.5, -0.5, 3).into_shape((1, 3)).unwrap(), Identity)
+		]);
+		let inputs = Array::from_vec(vec![
+			0.0, 1.0,
+			1.0, 0.0,
+			0.5, -0.5
+		]).into_shape((3, 2)).unwrap();
+		let batch_outputs = net.predict_batch(inputs.view()).to_owned();
+		assert_eq!(batch_outputs.dim(), (3, 1));
+		for (input, batch_output) in inputs.genrows().into_iter().zip(batch_outputs.genrows()) {
+			let output = net.predict(input).to_owned();
+			assert_relative_eq!(output[0], batch_output[0], epsilon = 1e-6);
+		}
+	}
+
+	#[test]
+	fn softmax_output() {
+		let mut layer = FullyConnectedLayer::with_weights(
+			Array1::linspace(-1.0, 1.0, 9).into_shape((3, 3)).unwrap(), Activation::Identity);
+		layer.softmax = true;
+		let outputs = layer.feed_forward(aview1(&[0.5, -0.5])).to_owned();
+		assert_relative_eq!(outputs.scalar_sum(), 1.0, epsilon = 1e-6);
+		let gradients = layer.calculate_output_gradients(aview1(&[0.0, 1.0, 0.0]), Loss::CategoricalCrossEntropy).gradients_view().to_owned();
+		assert_eq!(gradients.slice(s![..-1]), arr1(&[0.0, 1.0, 0.0]) - &outputs);
+		let batch_outputs = layer.feed_forward_batch(aview2(&[[0.5, -0.5], [0.0, 0.0]])).to_owned();
+		assert_eq!(batch_outputs.row(0), outputs);
+		assert_relative_eq!(batch_outputs.row(1).scalar_sum(), 1.0, epsilon = 1e-6);
+	}
+
+	#[test]
+	fn dropout_only_while_training() {
+		use self::Activation::Identity;
+		let mut layer = FullyConnectedLayer::with_weights(Array2::from_elem((1000, 2), 1.0), Identity);
+		layer.dropout = Dropout::new(0.5);
+		let mut rng = rng::from_seed(42);
+		let outputs = layer.feed_forward_dropout(aview1(&[1.0]), &mut rng).to_owned();
+		let dropped = outputs.iter().filter(|&&output| output == 0.0).count();
+		assert!(outputs.iter().all(|&output| output == 0.0 || output == 4.0));
+		assert!(dropped > 400 && dropped < 600, "dropped outputs: {}", dropped);
+		assert_eq!(layer.feed_forward(aview1(&[1.0])), Array1::from_elem(1000, 2.0));
+		let batch_outputs = layer.feed_forward_batch_dropout(aview2(&[[1.0], [1.0]]), &mut rng).to_owned();
+		assert!(batch_outputs.iter().all(|&output| output == 0.0 || output == 4.0));
+		assert_eq!(batch_outputs, &layer.dropout.batch_mask * 2.0);
+	}
+
+	#[test]
+	fn find_non_finite_layer() {
+		use self::Activation::{Tanh, Identity};
+		let mut net = NeuralNet::from_topology(
+			Topology::input(2).layer(3, Tanh).output(1, Identity));
+		assert_eq!(net.find_non_finite_layer(), None);
+		net.layers[1].weights[(0, 2)] = f32::NAN;
+		assert_eq!(net.find_non_finite_layer(), Some(1));
+		net.feed_forward(aview1(&[1.0, 2.0]));
+		net.layers[0].outputs[1] = f32::INFINITY;
+		assert_eq!(net.find_non_finite_layer(), Some(0));
+	}
+
+	#[test]
+	fn predict_applies_scaling() {
+		use self::Activation::Identity;
+		use scaling::{Scaler, ScalingFit};
+		let fitted = |values: &[f32]| {
+			let mut fit = ScalingFit::new(1);
+			for value in values {
+				fit.add(aview1(&[*value]));
+			}
+			fit.finish(&Scaler::MinMax)
+		};
+		let mut net = NeuralNet::from_vec(vec![
+			FullyConnectedLayer::with_weights(Array::from_vec(vec![1.0, 0.0]).into_shape((1, 2)).unwrap(), Identity)
+		]);
+		net.set_scaling(SampleScaling{
+			inputs : fitted(&[0.0, 10.0]),
+			targets: fitted(&[100.0, 200.0])
+		});
+		assert_eq!(net.feed_forward(aview1(&[0.5])), aview1(&[0.5]));
+		assert_eq!(net.predict(&[5.0]), aview1(&[150.0]));
+		#[cfg(feature = "serde_support")]
+		{
+			let mut decoded: NeuralNet = ::bincode::deserialize(&::bincode::serialize(&net).unwrap()).unwrap();
+			assert_eq!(decoded.predict(&[5.0]), aview1(&[150.0]));
+		}
+	}
+
 	#[test]
 	#[ignore]
 	fn equivalence() {
@@ -490,10 +1137,16 @@ mod tests {
 					1.0, 2.0, 3.0,
 					4.0, 5.0, 6.0
 				]).into_shape((2, 3)).unwrap(),
-			delta_weights: Array::zeros((2, 3)),
+			weight_gradients: Array::zeros((2, 3)),
+			optimizer: OptimizerState::new(Optimizer::Momentum, (2, 3)),
 			outputs: Array::zeros(2),
 			gradients: Array::zeros(3),
-			activation: Tanh
+			activation: Tanh,
+			softmax: false,
+			dropout: Dropout::default(),
+			regularization: Regularization::default(),
+			batch_outputs: Array::zeros((0, 2)),
+			batch_gradients: Array::zeros((0, 2))
 		};
 
 		println!("2");
@@ -503,10 +1156,16 @@ mod tests {
 					1.0, 2.0, 3.0,
 					4.0, 5.0, 6.0
 				]).into_shape((2, 3)).unwrap(),
-			delta_weights: Array::zeros((2, 3)),
+			weight_gradients: Array::zeros((2, 3)),
+			optimizer: OptimizerState::new(Optimizer::Momentum, (2, 3)),
 			outputs: Array::zeros(2),
 			gradients: Array::zeros(3),
-			activation: Identity
+			activation: Identity,
+			softmax: false,
+			dropout: Dropout::default(),
+			regularization: Regularization::default(),
+			batch_outputs: Array::zeros((0, 2)),
+			batch_gradients: Array::zeros((0, 2))
 		};
 
 		println!("3");
@@ -517,10 +1176,16 @@ mod tests {
 					0.0, 1.0, 0.0,
 					0.0, 0.0, 1.0
 				]).into_shape((3, 3)).unwrap(),
-			delta_weights: Array::zeros((3, 3)),
+			weight_gradients: Array::zeros((3, 3)),
+			optimizer: OptimizerState::new(Optimizer::Momentum, (3, 3)),
 			outputs: Array::zeros(2),
 			gradients: Array::zeros(3),
-			activation: Tanh
+			activation: Tanh,
+			softmax: false,
+			dropout: Dropout::default(),
+			regularization: Regularization::default(),
+			batch_outputs: Array::zeros((0, 2)),
+			batch_gradients: Array::zeros((0, 2))
 		};
 
 		println!("4");
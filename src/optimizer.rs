@@ -0,0 +1,494 @@
+//! Provides optimizers that define how the weights of a neural net
+//! are updated based on their gradients during training.
+
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+use ndarray::prelude::*;
+use ndarray::{Zip, Ix};
+
+use traits::{LearnRate, LearnMomentum};
+use errors::ErrorKind::InvalidOptimizer;
+use errors::Result;
+
+/// Represents an optimizer which updates the weights of a neural net during training.
+///
+/// Optimizers that adapt the step size for every weight individually
+/// (`AdaGrad`, `RMSProp` and `Adam`) usually require much smaller learn rates
+/// than plain gradient descent, e.g. `0.001` to `0.01`.
+///
+/// Other update rules are supported with `Optimizer::custom`.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum Optimizer {
+	/// Stochastic gradient descent with classical momentum.
+	///
+	/// Uses the learn rate and the learn momentum.
+	#[default]
+	Momentum,
+
+	/// Stochastic gradient descent with Nesterov momentum.
+	///
+	/// Uses the learn rate and the learn momentum.
+	Nesterov,
+
+	/// `AdaGrad`: Scales the learn rate of every weight down by
+	/// the accumulated squares of all of its previous gradients.
+	///
+	/// Uses the learn rate only.
+	AdaGrad{
+		/// Small constant that prevents divisions by zero.
+		epsilon: f32
+	},
+
+	/// `RMSProp`: Scales the learn rate of every weight down by
+	/// the moving average of the squares of its recent gradients.
+	///
+	/// Uses the learn rate only.
+	RMSProp{
+		/// Decay rate of the moving average within *(0, 1)*.
+		decay: f32,
+
+		/// Small constant that prevents divisions by zero.
+		epsilon: f32
+	},
+
+	/// Adam: Adaptive moment estimation based on moving averages
+	/// of the gradients and of their squares.
+	///
+	/// Uses the learn rate only.
+	Adam{
+		/// Decay rate of the first moment estimates within *[0, 1)*.
+		beta1: f32,
+
+		/// Decay rate of the second moment estimates within *(0, 1)*.
+		beta2: f32,
+
+		/// Small constant that prevents divisions by zero.
+		epsilon: f32
+	},
+
+	/// The given custom update rule.
+	///
+	/// Use `Optimizer::custom` to construct this.
+	///
+	/// Neural networks and checkpoints with a custom optimizer cannot be serialized.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	Custom(CustomOptimizer),
+}
+
+/// A rule to update the weights of a layer based on their gradients
+/// that can be used as optimizer with `Optimizer::custom`.
+///
+/// All gradients given to the update rule are expected to point into the
+/// direction that reduces the error, so they have to be added onto the weights.
+pub trait UpdateRule: Send + Sync + 'static {
+	/// The state of this update rule for the weights of a single layer.
+	type State: Clone + Send + Sync + 'static;
+
+	/// Creates a fresh state for weights of the given shape.
+	fn new_state(&self, shape: (usize, usize)) -> Self::State;
+
+	/// Updates the given weights based on the given gradients of equal dimensions
+	/// with the given learn rate and learn momentum and advances the given state.
+	fn update(&self,
+	          state     : &mut Self::State,
+	          weights   : ArrayViewMut2<f32>,
+	          gradients : ArrayView2<f32>,
+	          learn_rate: f32,
+	          learn_mom : f32);
+}
+
+/// Object safe form of an `UpdateRule` with its state type erased.
+trait ErasedUpdateRule: Send + Sync {
+	fn new_state(&self, shape: (usize, usize)) -> Box<dyn Any + Send + Sync>;
+	fn clone_state(&self, state: &(dyn Any + Send + Sync)) -> Box<dyn Any + Send + Sync>;
+	fn update(&self,
+	          state     : &mut (dyn Any + Send + Sync),
+	          weights   : ArrayViewMut2<f32>,
+	          gradients : ArrayView2<f32>,
+	          learn_rate: f32,
+	          learn_mom : f32);
+}
+
+impl<U: UpdateRule> ErasedUpdateRule for U {
+	fn new_state(&self, shape: (usize, usize)) -> Box<dyn Any + Send + Sync> {
+		Box::new(UpdateRule::new_state(self, shape))
+	}
+
+	fn clone_state(&self, state: &(dyn Any + Send + Sync)) -> Box<dyn Any + Send + Sync> {
+		Box::new(state.downcast_ref::<U::State>().expect("state must belong to the update rule").clone())
+	}
+
+	fn update(&self,
+	          state     : &mut (dyn Any + Send + Sync),
+	          weights   : ArrayViewMut2<f32>,
+	          gradients : ArrayView2<f32>,
+	          learn_rate: f32,
+	          learn_mom : f32)
+	{
+		let state = state.downcast_mut::<U::State>().expect("state must belong to the update rule");
+		UpdateRule::update(self, state, weights, gradients, learn_rate, learn_mom)
+	}
+}
+
+/// A custom update rule shared by all layers of a neural network.
+///
+/// Custom optimizers are only equal if they share the same update rule.
+#[derive(Clone)]
+pub struct CustomOptimizer(Arc<dyn ErasedUpdateRule>);
+
+impl fmt::Debug for CustomOptimizer {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CustomOptimizer(_)")
+	}
+}
+
+impl PartialEq for CustomOptimizer {
+	fn eq(&self, other: &Self) -> bool {
+		Arc::ptr_eq(&self.0, &other.0)
+	}
+}
+
+impl Optimizer {
+	/// Creates an optimizer that updates the weights of every layer
+	/// with the given update rule.
+	pub fn custom<U: UpdateRule>(rule: U) -> Self {
+		Optimizer::Custom(CustomOptimizer(Arc::new(rule)))
+	}
+
+	/// Returns the `AdaGrad` optimizer with commonly used parameters.
+	pub fn ada_grad() -> Self {
+		Optimizer::AdaGrad{epsilon: 1e-8}
+	}
+
+	/// Returns the `RMSProp` optimizer with commonly used parameters.
+	pub fn rms_prop() -> Self {
+		Optimizer::RMSProp{decay: 0.9, epsilon: 1e-8}
+	}
+
+	/// Returns the Adam optimizer with commonly used parameters.
+	pub fn adam() -> Self {
+		Optimizer::Adam{beta1: 0.9, beta2: 0.999, epsilon: 1e-8}
+	}
+
+	/// Checks if the parameters of this optimizer are valid.
+	pub fn check_validity(&self) -> Result<()> {
+		use self::Optimizer::*;
+		let valid = match *self {
+			Momentum  |
+			Nesterov  |
+			Custom(_) => true,
+			AdaGrad{epsilon} => {
+				epsilon > 0.0
+			}
+			RMSProp{decay, epsilon} => {
+				decay > 0.0 && decay < 1.0 && epsilon > 0.0
+			}
+			Adam{beta1, beta2, epsilon} => {
+				(0.0..1.0).contains(&beta1) && beta2 > 0.0 && beta2 < 1.0 && epsilon > 0.0
+			}
+		};
+		if valid { Ok(()) } else { Err(InvalidOptimizer) }
+	}
+}
+
+/// The state of an optimizer for the weights of a single layer.
+///
+/// All gradients given to the optimizer are expected to point into the
+/// direction that reduces the error, so they are added onto the weights.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub enum OptimizerState {
+	/// State of `Optimizer::Momentum`.
+	Momentum{
+		delta_weights: Array2<f32>
+	},
+
+	/// State of `Optimizer::Nesterov`.
+	Nesterov{
+		velocity: Array2<f32>
+	},
+
+	/// State of `Optimizer::AdaGrad`.
+	AdaGrad{
+		epsilon: f32,
+		cache  : Array2<f32>
+	},
+
+	/// State of `Optimizer::RMSProp`.
+	RMSProp{
+		decay  : f32,
+		epsilon: f32,
+		cache  : Array2<f32>
+	},
+
+	/// State of `Optimizer::Adam`.
+	Adam{
+		beta1   : f32,
+		beta2   : f32,
+		epsilon : f32,
+		steps   : i32,
+		moment  : Array2<f32>,
+		velocity: Array2<f32>
+	},
+
+	/// State of `Optimizer::Custom`.
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	Custom(CustomState),
+}
+
+/// The state of a custom update rule for the weights of a single layer.
+///
+/// Custom states only compare their update rules.
+pub struct CustomState {
+	optimizer: CustomOptimizer,
+	state    : Box<dyn Any + Send + Sync>,
+}
+
+impl Clone for CustomState {
+	fn clone(&self) -> Self {
+		CustomState{
+			optimizer: self.optimizer.clone(),
+			state    : self.optimizer.0.clone_state(&*self.state),
+		}
+	}
+}
+
+impl fmt::Debug for CustomState {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "CustomState(_)")
+	}
+}
+
+impl PartialEq for CustomState {
+	fn eq(&self, other: &Self) -> bool {
+		self.optimizer == other.optimizer
+	}
+}
+
+impl OptimizerState {
+	/// Creates a fresh state of the given optimizer for weights of the given shape.
+	///
+	/// All buffers must be initialized with zeros or else computation
+	/// in the first iteration will be screwed!
+	pub fn new(optimizer: Optimizer, shape: (Ix, Ix)) -> Self {
+		use self::Optimizer::*;
+		match optimizer {
+			Momentum => OptimizerState::Momentum{
+				delta_weights: Array2::zeros(shape)
+			},
+			Nesterov => OptimizerState::Nesterov{
+				velocity: Array2::zeros(shape)
+			},
+			AdaGrad{epsilon} => OptimizerState::AdaGrad{
+				epsilon,
+				cache: Array2::zeros(shape)
+			},
+			RMSProp{decay, epsilon} => OptimizerState::RMSProp{
+				decay,
+				epsilon,
+				cache: Array2::zeros(shape)
+			},
+			Adam{beta1, beta2, epsilon} => OptimizerState::Adam{
+				beta1,
+				beta2,
+				epsilon,
+				steps   : 0,
+				moment  : Array2::zeros(shape),
+				velocity: Array2::zeros(shape)
+			},
+			Custom(optimizer) => OptimizerState::Custom(CustomState{
+				state    : optimizer.0.new_state(shape),
+				optimizer: optimizer,
+			}),
+		}
+	}
+
+	/// Returns the optimizer this state belongs to.
+	pub fn optimizer(&self) -> Optimizer {
+		use self::OptimizerState::*;
+		match *self {
+			Momentum{..} => Optimizer::Momentum,
+			Nesterov{..} => Optimizer::Nesterov,
+			AdaGrad{epsilon, ..} => Optimizer::AdaGrad{epsilon},
+			RMSProp{decay, epsilon, ..} => Optimizer::RMSProp{decay, epsilon},
+			Adam{beta1, beta2, epsilon, ..} => Optimizer::Adam{beta1, beta2, epsilon},
+			Custom(ref custom) => Optimizer::Custom(custom.optimizer.clone()),
+		}
+	}
+
+	/// Updates the given weights based on the given gradients
+	/// of equal dimensions and advances the state of this optimizer.
+	pub fn update(&mut self,
+	              weights   : &mut Array2<f32>,
+	              gradients : ArrayView2<f32>,
+	              learn_rate: LearnRate,
+	              learn_mom : LearnMomentum)
+	{
+		debug_assert_eq!(weights.dim(), gradients.dim());
+
+		let rate = learn_rate.0;
+		let mom  = learn_mom.0;
+
+		use self::OptimizerState::*;
+		match *self {
+			Momentum{ref mut delta_weights} => {
+				Zip::from(weights).and(delta_weights).and(gradients)
+					.apply(|weight, delta_weight, &gradient| {
+						*delta_weight = rate * gradient + mom * *delta_weight;
+						*weight += *delta_weight;
+					});
+			}
+			Nesterov{ref mut velocity} => {
+				Zip::from(weights).and(velocity).and(gradients)
+					.apply(|weight, velocity, &gradient| {
+						let prev_velocity = *velocity;
+						*velocity = mom * prev_velocity + rate * gradient;
+						*weight += (1.0 + mom) * *velocity - mom * prev_velocity;
+					});
+			}
+			AdaGrad{epsilon, ref mut cache} => {
+				Zip::from(weights).and(cache).and(gradients)
+					.apply(|weight, cache, &gradient| {
+						*cache += gradient * gradient;
+						*weight += rate * gradient / (cache.sqrt() + epsilon);
+					});
+			}
+			RMSProp{decay, epsilon, ref mut cache} => {
+				Zip::from(weights).and(cache).and(gradients)
+					.apply(|weight, cache, &gradient| {
+						*cache = decay * *cache + (1.0 - decay) * gradient * gradient;
+						*weight += rate * gradient / (cache.sqrt() + epsilon);
+					});
+			}
+			Adam{beta1, beta2, epsilon, ref mut steps, ref mut moment, ref mut velocity} => {
+				*steps = steps.saturating_add(1);
+				let correction1 = 1.0 - beta1.powi(*steps);
+				let correction2 = 1.0 - beta2.powi(*steps);
+				Zip::from(weights).and(moment).and(velocity).and(gradients)
+					.apply(|weight, moment, velocity, &gradient| {
+						*moment   = beta1 * *moment   + (1.0 - beta1) * gradient;
+						*velocity = beta2 * *velocity + (1.0 - beta2) * gradient * gradient;
+						let moment_hat   = *moment   / correction1;
+						let velocity_hat = *velocity / correction2;
+						*weight += rate * moment_hat / (velocity_hat.sqrt() + epsilon);
+					});
+			}
+			Custom(ref mut custom) => {
+				custom.optimizer.0.update(&mut *custom.state, weights.view_mut(), gradients, rate, mom)
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn all_optimizers() -> Vec<Optimizer> {
+		vec![
+			Optimizer::Momentum,
+			Optimizer::Nesterov,
+			Optimizer::ada_grad(),
+			Optimizer::rms_prop(),
+			Optimizer::adam()
+		]
+	}
+
+	#[test]
+	fn defaults_are_valid() {
+		for optimizer in all_optimizers() {
+			assert_eq!(optimizer.check_validity(), Ok(()));
+		}
+	}
+
+	#[test]
+	fn invalid_parameters() {
+		assert_eq!(Optimizer::AdaGrad{epsilon: 0.0}.check_validity(), Err(InvalidOptimizer));
+		assert_eq!(Optimizer::RMSProp{decay: 1.0, epsilon: 1e-8}.check_validity(), Err(InvalidOptimizer));
+		assert_eq!(Optimizer::Adam{beta1: 0.9, beta2: 0.0, epsilon: 1e-8}.check_validity(), Err(InvalidOptimizer));
+	}
+
+	#[test]
+	fn state_roundtrip() {
+		for optimizer in all_optimizers() {
+			assert_eq!(OptimizerState::new(optimizer.clone(), (2, 3)).optimizer(), optimizer);
+		}
+	}
+
+	#[test]
+	fn follows_gradients() {
+		let gradients = Array::from_vec(vec![
+			 1.0, -1.0,  0.5,
+			-0.5,  2.0, -2.0]).into_shape((2, 3)).unwrap();
+		for optimizer in all_optimizers() {
+			let mut state   = OptimizerState::new(optimizer.clone(), (2, 3));
+			let mut weights = Array2::zeros((2, 3));
+			state.update(&mut weights, gradients.view(), LearnRate(0.1), LearnMomentum(0.5));
+			Zip::from(&weights).and(&gradients).apply(|&weight, &gradient| {
+				assert!(weight.signum() == gradient.signum(), "{:?}", optimizer);
+			});
+		}
+	}
+
+	#[test]
+	fn momentum() {
+		let gradients = Array::from_vec(vec![1.0, 2.0]).into_shape((1, 2)).unwrap();
+		let mut state   = OptimizerState::new(Optimizer::Momentum, (1, 2));
+		let mut weights = Array2::zeros((1, 2));
+		state.update(&mut weights, gradients.view(), LearnRate(0.5), LearnMomentum(0.5));
+		state.update(&mut weights, gradients.view(), LearnRate(0.5), LearnMomentum(0.5));
+		assert_eq!(weights, Array::from_vec(vec![1.25, 2.5]).into_shape((1, 2)).unwrap());
+	}
+
+	/// Plain gradient descent that counts its updates.
+	struct CountingDescent;
+
+	impl UpdateRule for CountingDescent {
+		type State = u32;
+
+		fn new_state(&self, _: (usize, usize)) -> u32 {
+			0
+		}
+
+		fn update(&self, state: &mut u32, mut weights: ArrayViewMut2<f32>, gradients: ArrayView2<f32>, rate: f32, _: f32) {
+			*state += 1;
+			weights.scaled_add(rate, &gradients);
+		}
+	}
+
+	#[test]
+	fn custom() {
+		let optimizer = Optimizer::custom(CountingDescent);
+		assert_eq!(optimizer.check_validity(), Ok(()));
+		assert_ne!(optimizer, Optimizer::custom(CountingDescent));
+		let gradients = Array::from_vec(vec![1.0, -2.0]).into_shape((1, 2)).unwrap();
+		let mut state   = OptimizerState::new(optimizer.clone(), (1, 2));
+		let mut weights = Array2::zeros((1, 2));
+		state.update(&mut weights, gradients.view(), LearnRate(0.5), LearnMomentum(0.5));
+		let mut cloned = state.clone();
+		cloned.update(&mut weights, gradients.view(), LearnRate(0.5), LearnMomentum(0.5));
+		assert_eq!(weights, Array::from_vec(vec![1.0, -2.0]).into_shape((1, 2)).unwrap());
+		assert_eq!(state.optimizer(), optimizer);
+		match (state, cloned) {
+			(OptimizerState::Custom(state), OptimizerState::Custom(cloned)) => {
+				assert_eq!(state.state.downcast_ref::<u32>(), Some(&1));
+				assert_eq!(cloned.state.downcast_ref::<u32>(), Some(&2));
+			}
+			_ => panic!("expected custom optimizer states")
+		}
+	}
+
+	#[test]
+	fn adam_first_step() {
+		let gradients = Array::from_vec(vec![0.01, -100.0]).into_shape((1, 2)).unwrap();
+		let mut state   = OptimizerState::new(Optimizer::adam(), (1, 2));
+		let mut weights = Array2::zeros((1, 2));
+		state.update(&mut weights, gradients.view(), LearnRate(0.1), LearnMomentum(0.5));
+		// The first step of Adam is independent of the gradient magnitudes.
+		assert_relative_eq!(weights[(0, 0)],  0.1, epsilon = 1e-4);
+		assert_relative_eq!(weights[(0, 1)], -0.1, epsilon = 1e-4);
+	}
+}
@@ -2,7 +2,10 @@
 //! needs in order to create, train and use neural networks.
 
 #[doc(no_inline)]
-pub use activation::Activation;
+pub use activation::{Activation, OutputActivation};
+
+#[doc(no_inline)]
+pub use optimizer::{Optimizer, UpdateRule};
 
 #[doc(no_inline)]
 pub use neural_net::NeuralNet;
@@ -13,14 +16,59 @@ pub use traits::{Predict};
 #[doc(no_inline)]
 pub use topology::{Topology, TopologyBuilder, Layer};
 
+#[doc(no_inline)]
+pub use initializer::Initializer;
+
+#[doc(no_inline)]
+pub use scaling::{Scaler, Scaling};
+
+#[doc(no_inline)]
+pub use loss::Loss;
+
+#[doc(no_inline)]
+pub use regularization::Regularization;
+
+#[doc(no_inline)]
+pub use clipping::GradientClipping;
+
 #[doc(no_inline)]
 pub use errors::{Result, ErrorKind};
 
 #[doc(no_inline)]
-pub use mentor::configs::{LogConfig, Scheduling, Criterion};
+pub use mentor::configs::{LogConfig, Scheduling, Criterion, BatchSize};
 
 #[doc(no_inline)]
 pub use mentor::training::{Mentor, MentorBuilder};
 
 #[doc(no_inline)]
 pub use mentor::samples::{Sample, SampleView};
+
+#[doc(no_inline)]
+pub use mentor::source::{SampleSource, MemorySource, FileSource};
+
+#[doc(no_inline)]
+pub use mentor::csv::{CsvLoader, Columns, MissingValues};
+
+#[doc(no_inline)]
+pub use mentor::idx::{load_idx, read_idx};
+
+#[doc(no_inline)]
+pub use mentor::logger::Stats;
+
+#[doc(no_inline)]
+pub use mentor::adapt::LearnRatePolicy;
+
+#[doc(no_inline)]
+pub use mentor::report::{TrainingReport, StopReason};
+
+#[doc(no_inline)]
+pub use mentor::observer::{TrainingObserver, Observation, MetricsWriter, MetricsFormat};
+
+#[doc(no_inline)]
+pub use mentor::logger::{TimeStepsLogger, IterationsLogger};
+
+#[doc(no_inline)]
+pub use mentor::checkpoint::Checkpoint;
+
+#[doc(no_inline)]
+pub use mentor::split::{Split, CrossValidation, FoldReport, k_folds};
@@ -0,0 +1,163 @@
+//! Provides weight regularization that counteracts overfitting
+//! by keeping the weights of a neural network small.
+//!
+//! Regularization never applies to the weights of bias neurons.
+
+use ndarray::prelude::*;
+use ndarray::Zip;
+
+use errors::ErrorKind::InvalidRegularization;
+use errors::Result;
+use traits::LearnRate;
+
+/// Represents the regularization of the weights of a layer during training.
+///
+/// All terms can be combined and are disabled if zero which is the default.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Regularization {
+	/// Strength of the L1 penalty *l1⋅Σ|w|* added to the loss.
+	///
+	/// Drives weights of unimportant connections towards zero.
+	pub l1: f32,
+
+	/// Strength of the L2 penalty *½⋅l2⋅Σw²* added to the loss.
+	pub l2: f32,
+
+	/// Rate of the decoupled weight decay that shrinks every weight
+	/// by *learn_rate⋅weight_decay⋅w* after every weight update.
+	///
+	/// Unlike the L2 penalty it is independent of the optimizer
+	/// and not part of the loss.
+	///
+	/// *learn_rate⋅weight_decay* must be below one, so that the decay never flips
+	/// the signs of the weights. Adapted learn rates are assumed to be at most `0.9`.
+	pub weight_decay: f32,
+}
+
+impl Regularization {
+	/// Returns an L1 regularization with the given strength.
+	pub fn l1(strength: f32) -> Self {
+		Regularization{l1: strength, ..Regularization::default()}
+	}
+
+	/// Returns an L2 regularization with the given strength.
+	pub fn l2(strength: f32) -> Self {
+		Regularization{l2: strength, ..Regularization::default()}
+	}
+
+	/// Returns a decoupled weight decay with the given rate.
+	pub fn weight_decay(rate: f32) -> Self {
+		Regularization{weight_decay: rate, ..Regularization::default()}
+	}
+
+	/// Returns the combination of this and the given regularization.
+	pub fn and(self, other: Regularization) -> Self {
+		Regularization{
+			l1          : self.l1 + other.l1,
+			l2          : self.l2 + other.l2,
+			weight_decay: self.weight_decay + other.weight_decay,
+		}
+	}
+
+	/// Returns `true` if this regularization has no effect.
+	pub fn is_none(&self) -> bool {
+		*self == Regularization::default()
+	}
+
+	/// Checks if all terms of this regularization are non-negative and finite
+	/// and if the weight decay never flips the signs of the weights for learn
+	/// rates up to the given maximum learn rate.
+	pub(crate) fn check_validity(&self, max_rate: LearnRate) -> Result<()> {
+		let valid = |term: f32| term >= 0.0 && term.is_finite();
+		if valid(self.l1) && valid(self.l2) && valid(self.weight_decay) &&
+			max_rate.0 * self.weight_decay < 1.0
+		{
+			Ok(())
+		}
+		else {
+			Err(InvalidRegularization)
+		}
+	}
+
+	/// Returns the penalty of the given weights that is added to the loss.
+	///
+	/// The last column holds the weights of the bias neuron and is ignored.
+	pub(crate) fn penalty(&self, weights: ArrayView2<f32>) -> f64 {
+		if self.l1 == 0.0 && self.l2 == 0.0 {
+			return 0.0
+		}
+		let (l1, l2) = (f64::from(self.l1), f64::from(self.l2));
+		weights
+			.slice(s![.., ..-1])
+			.iter()
+			.map(|&weight| {
+				let weight = f64::from(weight);
+				l1 * weight.abs() + 0.5 * l2 * weight * weight
+			})
+			.sum()
+	}
+
+	/// Adds the negative gradients of the penalty of the given weights
+	/// onto the given weight gradients.
+	///
+	/// The last column holds the weights of the bias neuron and is ignored.
+	pub(crate) fn apply_penalty(&self, weights: ArrayView2<f32>, mut gradients: ArrayViewMut2<f32>) {
+		if self.l1 == 0.0 && self.l2 == 0.0 {
+			return
+		}
+		let (l1, l2) = (self.l1, self.l2);
+		Zip::from(gradients.slice_mut(s![.., ..-1]))
+			.and(weights.slice(s![.., ..-1]))
+			.apply(|gradient, &weight| {
+				let sign = if weight > 0.0 { 1.0 } else if weight < 0.0 { -1.0 } else { 0.0 };
+				*gradient -= l1 * sign + l2 * weight;
+			});
+	}
+
+	/// Shrinks the given weights by the decoupled weight decay.
+	///
+	/// The last column holds the weights of the bias neuron and is ignored.
+	pub(crate) fn apply_decay(&self, mut weights: ArrayViewMut2<f32>, rate: LearnRate) {
+		if self.weight_decay == 0.0 {
+			return
+		}
+		let factor = 1.0 - rate.0 * self.weight_decay;
+		weights.slice_mut(s![.., ..-1]).mapv_inplace(|weight| weight * factor);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn penalty() {
+		let weights = arr2(&[[1.0, -2.0, 5.0], [0.0, 3.0, -7.0]]);
+		let regularization = Regularization::l1(0.5).and(Regularization::l2(2.0));
+		assert_eq!(regularization.penalty(weights.view()), 0.5 * 6.0 + 14.0);
+		let mut gradients = Array2::zeros((2, 3));
+		regularization.apply_penalty(weights.view(), gradients.view_mut());
+		assert_eq!(gradients, arr2(&[[-2.5, 4.5, 0.0], [0.0, -6.5, 0.0]]));
+		assert_eq!(Regularization::default().penalty(weights.view()), 0.0);
+	}
+
+	#[test]
+	fn decay() {
+		let mut weights = arr2(&[[1.0, -2.0, 5.0]]);
+		Regularization::weight_decay(0.5).apply_decay(weights.view_mut(), LearnRate(0.5));
+		assert_eq!(weights, arr2(&[[0.75, -1.5, 5.0]]));
+	}
+
+	#[test]
+	fn validity() {
+		assert!(Regularization::default().is_none());
+		let rate = LearnRate(0.5);
+		assert_eq!(Regularization::l2(0.1).check_validity(rate), Ok(()));
+		assert_eq!(Regularization::l1(-0.1).check_validity(rate), Err(InvalidRegularization));
+		assert_eq!(Regularization::weight_decay(f32::NAN).check_validity(rate), Err(InvalidRegularization));
+		assert_eq!(Regularization::weight_decay(1.5).check_validity(rate), Ok(()));
+		assert_eq!(Regularization::weight_decay(2.0).check_validity(rate), Err(InvalidRegularization));
+		assert_eq!(Regularization::weight_decay(3.0).check_validity(rate), Err(InvalidRegularization));
+	}
+}
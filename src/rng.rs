@@ -0,0 +1,110 @@
+//! Provides the random number generator used for weight initialization
+//! and sample scheduling so that training runs can be reproduced.
+
+use rand::{Rand, Rng, thread_rng};
+
+/// The random number generator used throughout the library.
+///
+/// It is a xorshift generator that is fast and its sequence depends
+/// only on its seed which is required for reproducible training runs.
+/// Its state is part of checkpoints so that resumed trainings go on
+/// with the same random numbers.
+///
+/// Draws the same numbers as the `XorShiftRng` of the `rand` crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Prng {
+	state: [u32; 4],
+}
+
+impl Prng {
+	/// Creates a new random number generator with the given state.
+	///
+	/// # Panics
+	///
+	/// If the state is all zero.
+	fn from_state(state: [u32; 4]) -> Prng {
+		assert!(state.iter().any(|&word| word != 0), "the state of the generator must not be all zero");
+		Prng{state}
+	}
+}
+
+impl Rng for Prng {
+	fn next_u32(&mut self) -> u32 {
+		let [x, y, z, w] = self.state;
+		let t    = x ^ (x << 11);
+		let next = w ^ (w >> 19) ^ (t ^ (t >> 8));
+		self.state = [y, z, w, next];
+		next
+	}
+}
+
+impl Rand for Prng {
+	fn rand<R: Rng>(rng: &mut R) -> Prng {
+		let mut state: [u32; 4] = rng.gen();
+		while state == [0; 4] {
+			state = rng.gen();
+		}
+		Prng::from_state(state)
+	}
+}
+
+/// Creates a new random number generator from the given seed
+/// or from the thread local random number generator if there is none.
+pub fn new(seed: Option<u64>) -> Prng {
+	match seed {
+		Some(seed) => from_seed(seed),
+		None       => thread_rng().gen()
+	}
+}
+
+/// Creates a new random number generator from the given seed.
+///
+/// Similar seeds are spread via SplitMix64 so that they
+/// lead to entirely different sequences.
+pub fn from_seed(seed: u64) -> Prng {
+	fn split_mix(state: &mut u64) -> u64 {
+		*state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+		let mut z = *state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+		z ^ (z >> 31)
+	}
+	let mut state = seed;
+	let (fst, snd) = (split_mix(&mut state), split_mix(&mut state));
+	let words = [fst as u32, (fst >> 32) as u32, snd as u32, (snd >> 32) as u32];
+	if words.iter().all(|&word| word == 0) {
+		// An all zero seed is invalid for the generator.
+		return Prng::from_state([0x193a_6754, 0xa8a7_d469, 0x9783_0e05, 0x113b_a7bb])
+	}
+	Prng::from_state(words)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn same_seed_same_sequence() {
+		let (mut fst, mut snd) = (from_seed(42), from_seed(42));
+		for _ in 0..100 {
+			assert_eq!(fst.next_u64(), snd.next_u64());
+		}
+	}
+
+	#[test]
+	fn same_sequence_as_xor_shift() {
+		use rand::{SeedableRng, XorShiftRng};
+		let words = [1, 2, 3, 4];
+		let (mut prng, mut xor_shift) = (Prng::from_state(words), XorShiftRng::from_seed(words));
+		for _ in 0..100 {
+			assert_eq!(prng.next_u32(), xor_shift.next_u32());
+		}
+	}
+
+	#[test]
+	fn different_seeds_different_sequences() {
+		let (mut fst, mut snd) = (from_seed(0), from_seed(1));
+		assert!((0..10).any(|_| fst.next_u64() != snd.next_u64()));
+	}
+}
@@ -0,0 +1,334 @@
+//! Provides scalers that bring the inputs and targets of samples into similar ranges.
+//!
+//! Scalers are fitted to the training samples once before the training starts.
+//! The fitted scalings are stored within the trained neural network so that
+//! predictions apply the same transformation to their inputs and the inverse
+//! transformation to their outputs.
+
+use std::f64;
+
+use ndarray::prelude::*;
+
+use activation::{Activation, OutputActivation};
+use errors::ErrorKind::InvalidScaler;
+use errors::Result;
+use mentor::samples::Sample;
+
+/// Represents a strategy to scale the values of sample inputs or targets.
+///
+/// Every column, e.g. every input neuron, is fitted separately.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scaler {
+	/// Values are kept as they are.
+	Identity,
+
+	/// Values are scaled linearly into `[0,1]` by the minimum and maximum of their column.
+	MinMax,
+
+	/// Values are standardized by the mean and standard deviation of their column.
+	///
+	/// Standardized targets require an output activation function
+	/// that is unbounded in both directions, e.g. `Identity`.
+	ZScore,
+
+	/// The columns are scaled by the respective given scalers.
+	///
+	/// There must be one scaler for every column and none of them
+	/// may be another `PerColumn` scaler.
+	PerColumn(Vec<Scaler>),
+}
+
+impl Scaler {
+	/// Checks if this scaler is valid for the given number of columns.
+	pub(crate) fn check_validity(&self, len: usize) -> Result<()> {
+		match *self {
+			Scaler::PerColumn(ref scalers) => {
+				if scalers.len() != len || scalers.iter().any(|scaler| matches!(scaler, Scaler::PerColumn(_))) {
+					return Err(InvalidScaler)
+				}
+				Ok(())
+			}
+			_ => Ok(())
+		}
+	}
+
+	/// Checks if the outputs of the given output activation function
+	/// can reach the values of targets scaled by this scaler.
+	///
+	/// Values scaled with `MinMax` are within `[0,1]` which is within the
+	/// closure of the range of every output activation function.
+	pub(crate) fn check_targets(&self, activation: OutputActivation) -> Result<()> {
+		let standardized = match *self {
+			Scaler::PerColumn(ref scalers) => scalers.contains(&Scaler::ZScore),
+			ref scaler                     => *scaler == Scaler::ZScore
+		};
+		let unbounded = matches!(activation,
+			OutputActivation::Element(Activation::Identity) |
+			OutputActivation::Element(Activation::BentIdentity));
+		if standardized && !unbounded {
+			return Err(InvalidScaler)
+		}
+		Ok(())
+	}
+
+	/// Returns the scaler of the column with the given index.
+	fn column(&self, index: usize) -> &Scaler {
+		match *self {
+			Scaler::PerColumn(ref scalers) => &scalers[index],
+			ref scaler                     => scaler
+		}
+	}
+}
+
+/// Statistics of a single column required to fit scalers.
+#[derive(Debug, Copy, Clone)]
+///
+/// The mean and the sum of squared deviations from it are updated
+/// with Welford's algorithm which stays accurate for values with
+/// large offsets unlike the difference of the sum of squares and
+/// the squared sum.
+struct ColumnStats {
+	min       : f64,
+	max       : f64,
+	mean      : f64,
+	sum_sq_dev: f64,
+}
+
+impl Default for ColumnStats {
+	fn default() -> Self {
+		ColumnStats{
+			min       : f64::INFINITY,
+			max       : f64::NEG_INFINITY,
+			mean      : 0.0,
+			sum_sq_dev: 0.0,
+		}
+	}
+}
+
+/// Collects the column statistics of values in order to fit a scaler.
+#[derive(Debug, Clone)]
+pub(crate) struct ScalingFit {
+	count  : usize,
+	columns: Vec<ColumnStats>,
+}
+
+impl ScalingFit {
+	/// Creates a new fit for values with the given number of columns.
+	pub fn new(len: usize) -> Self {
+		ScalingFit{
+			count  : 0,
+			columns: vec![ColumnStats::default(); len],
+		}
+	}
+
+	/// Adds the given values, e.g. the input of a sample, to the statistics.
+	pub fn add(&mut self, values: ArrayView1<f32>) {
+		debug_assert_eq!(values.len(), self.columns.len());
+		self.count += 1;
+		let count = self.count as f64;
+		for (stats, &value) in self.columns.iter_mut().zip(values.iter()) {
+			let value = f64::from(value);
+			let delta = value - stats.mean;
+			stats.min         = stats.min.min(value);
+			stats.max         = stats.max.max(value);
+			stats.mean       += delta / count;
+			stats.sum_sq_dev += delta * (value - stats.mean);
+		}
+	}
+
+	/// Fits the given scaler to the collected statistics.
+	///
+	/// Returns `None` if the scaler keeps all values as they are.
+	///
+	/// Columns with constant values are only shifted.
+	pub fn finish(&self, scaler: &Scaler) -> Option<Scaling> {
+		if *scaler == Scaler::Identity || self.count == 0 {
+			return None
+		}
+		let count = self.count as f64;
+		let (offsets, factors): (Vec<f32>, Vec<f32>) = self.columns
+			.iter()
+			.enumerate()
+			.map(|(index, stats)| {
+				let (offset, range) = match *scaler.column(index) {
+					Scaler::MinMax => (stats.min, stats.max - stats.min),
+					Scaler::ZScore => (stats.mean, (stats.sum_sq_dev / count).sqrt()),
+					Scaler::Identity     |
+					Scaler::PerColumn(_) => (0.0, 1.0)
+				};
+				let factor = if range > 0.0 && range.is_finite() { 1.0 / range } else { 1.0 };
+				(offset as f32, factor as f32)
+			})
+			.unzip();
+		Some(Scaling::new(offsets, factors))
+	}
+}
+
+/// A fitted scaler that transforms every column of values
+/// by `(value - offset) * factor`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub struct Scaling {
+	offsets: Array1<f32>,
+	factors: Array1<f32>,
+
+	#[cfg_attr(feature = "serde_support", serde(skip))]
+	buffer : Array1<f32>,
+}
+
+impl Scaling {
+	/// Creates a new scaling from the given offsets and factors per column.
+	fn new(offsets: Vec<f32>, factors: Vec<f32>) -> Self {
+		let len = offsets.len();
+		Scaling{
+			offsets: Array1::from_vec(offsets),
+			factors: Array1::from_vec(factors),
+			buffer : Array1::zeros(len),
+		}
+	}
+
+	/// Returns the offsets that are subtracted from the values of every column.
+	pub fn offsets(&self) -> ArrayView1<f32> {
+		self.offsets.view()
+	}
+
+	/// Returns the factors the shifted values of every column are multiplied with.
+	pub fn factors(&self) -> ArrayView1<f32> {
+		self.factors.view()
+	}
+
+	/// Scales the given values in place.
+	pub fn scale(&self, mut values: ArrayViewMut1<f32>) {
+		debug_assert_eq!(values.len(), self.offsets.len());
+		for (value, &offset, &factor) in izip!(values.iter_mut(), &self.offsets, &self.factors) {
+			*value = (*value - offset) * factor;
+		}
+	}
+
+	/// Reverts the scaling of the given values in place.
+	pub fn unscale(&self, mut values: ArrayViewMut1<f32>) {
+		debug_assert_eq!(values.len(), self.offsets.len());
+		for (value, &offset, &factor) in izip!(values.iter_mut(), &self.offsets, &self.factors) {
+			*value = *value / factor + offset;
+		}
+	}
+
+	/// Returns a scaled copy of the given values.
+	///
+	/// The copy is stored in an internal buffer to avoid allocations.
+	pub(crate) fn scaled(&mut self, values: ArrayView1<f32>) -> ArrayView1<f32> {
+		self.fill_buffer(values);
+		for (value, &offset, &factor) in izip!(self.buffer.iter_mut(), &self.offsets, &self.factors) {
+			*value = (*value - offset) * factor;
+		}
+		self.buffer.view()
+	}
+
+	/// Returns an unscaled copy of the given values.
+	///
+	/// The copy is stored in an internal buffer to avoid allocations.
+	pub(crate) fn unscaled(&mut self, values: ArrayView1<f32>) -> ArrayView1<f32> {
+		self.fill_buffer(values);
+		for (value, &offset, &factor) in izip!(self.buffer.iter_mut(), &self.offsets, &self.factors) {
+			*value = *value / factor + offset;
+		}
+		self.buffer.view()
+	}
+
+	/// Copies the given values into the internal buffer.
+	///
+	/// The buffer is not serialized and thus (re-)allocated on demand.
+	fn fill_buffer(&mut self, values: ArrayView1<f32>) {
+		if self.buffer.len() != values.len() {
+			self.buffer = Array1::zeros(values.len());
+		}
+		self.buffer.assign(&values);
+	}
+}
+
+/// The fitted scalings of sample inputs and targets.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
+pub(crate) struct SampleScaling {
+	pub inputs : Option<Scaling>,
+	pub targets: Option<Scaling>,
+}
+
+impl SampleScaling {
+	/// Returns `true` if neither inputs nor targets are scaled.
+	pub fn is_none(&self) -> bool {
+		self.inputs.is_none() && self.targets.is_none()
+	}
+
+	/// Scales the input and target of the given sample in place.
+	pub fn apply(&self, sample: &mut Sample) {
+		if let Some(ref inputs) = self.inputs {
+			inputs.scale(sample.input.view_mut())
+		}
+		if let Some(ref targets) = self.targets {
+			targets.scale(sample.target.view_mut())
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn fit(scaler: &Scaler, rows: &[[f32; 2]]) -> Scaling {
+		let mut fit = ScalingFit::new(2);
+		for row in rows {
+			fit.add(aview1(row));
+		}
+		fit.finish(scaler).unwrap()
+	}
+
+	#[test]
+	fn min_max() {
+		let scaling = fit(&Scaler::MinMax, &[[1.0, -4.0], [3.0, 4.0], [2.0, 0.0]]);
+		let mut values = arr1(&[2.0, 4.0]);
+		scaling.scale(values.view_mut());
+		assert_eq!(values, arr1(&[0.5, 1.0]));
+		scaling.unscale(values.view_mut());
+		assert_eq!(values, arr1(&[2.0, 4.0]));
+	}
+
+	#[test]
+	fn z_score() {
+		let scaling = fit(&Scaler::ZScore, &[[1.0, 5.0], [3.0, 5.0]]);
+		assert_eq!(scaling.offsets(), aview1(&[2.0, 5.0]));
+		assert_eq!(scaling.factors(), aview1(&[1.0, 1.0]));
+		let mut values = arr1(&[3.0, 6.0]);
+		scaling.scale(values.view_mut());
+		assert_eq!(values, arr1(&[1.0, 1.0]));
+	}
+
+	#[test]
+	fn z_score_large_offset() {
+		let scaling = fit(&Scaler::ZScore, &[[1e8, 0.0], [1e8 + 8.0, 0.0], [1e8 + 16.0, 0.0]]);
+		assert_eq!(scaling.offsets()[0], 1e8 + 8.0);
+		assert_relative_eq!(scaling.factors()[0], (3.0f32 / 128.0).sqrt(), epsilon = 1e-6);
+	}
+
+	#[test]
+	fn standardized_targets() {
+		use activation::Activation::{Identity, Logistic};
+		let per_column = Scaler::PerColumn(vec![Scaler::MinMax, Scaler::ZScore]);
+		assert_eq!(Scaler::ZScore.check_targets(Identity.into()), Ok(()));
+		assert_eq!(Scaler::MinMax.check_targets(Logistic.into()), Ok(()));
+		assert_eq!(Scaler::ZScore.check_targets(Logistic.into()), Err(InvalidScaler));
+		assert_eq!(per_column.check_targets(OutputActivation::SoftMax), Err(InvalidScaler));
+	}
+
+	#[test]
+	fn per_column() {
+		let scaler = Scaler::PerColumn(vec![Scaler::Identity, Scaler::MinMax]);
+		assert_eq!(scaler.check_validity(2), Ok(()));
+		assert_eq!(scaler.check_validity(3), Err(InvalidScaler));
+		assert_eq!(Scaler::PerColumn(vec![scaler.clone(), Scaler::MinMax]).check_validity(2), Err(InvalidScaler));
+		let scaling = fit(&scaler, &[[1.0, 2.0], [3.0, 6.0]]);
+		assert_eq!(scaling.offsets(), aview1(&[0.0, 2.0]));
+		assert_eq!(scaling.factors(), aview1(&[1.0, 0.25]));
+		assert!(ScalingFit::new(2).finish(&Scaler::Identity).is_none());
+	}
+}
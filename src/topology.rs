@@ -2,11 +2,12 @@
 //! which form the basis for topologies of neural networks.
 
 use std::slice::Iter;
-use activation::Activation;
+use activation::{Activation, OutputActivation};
+use initializer::Initializer;
 
 /// Represents the topology element for a fully connected layer
 /// with input neurons, output neurons and an activation function.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Layer{
 	/// Number of input neurons to this layer.
 	pub inputs: usize,
@@ -15,7 +16,27 @@ pub struct Layer{
 	pub outputs: usize,
 
 	/// Activation function for this layer.
-	pub activation: Activation
+	pub activation: Activation,
+
+	/// Initializer for the weights of this layer.
+	///
+	/// Defaults to the initializer suited for the activation function.
+	pub initializer: Initializer,
+
+	/// Initializer for the bias weights of this layer.
+	///
+	/// Defaults to `Initializer::Zeros`.
+	pub bias_initializer: Initializer,
+
+	/// Probability within `[0,1)` to drop out every output of this layer while training.
+	///
+	/// Defaults to `0`, i.e. no dropout.
+	pub dropout: f32,
+
+	/// Normalizes the outputs of this layer with softmax after the activation function.
+	///
+	/// Only output layers with `OutputActivation::SoftMax` normalize their outputs.
+	pub softmax: bool
 }
 
 impl Layer {
@@ -24,7 +45,11 @@ impl Layer {
 		Layer{
 			inputs: inputs,
 			outputs: outputs,
-			activation: activation
+			activation: activation,
+			initializer: Initializer::for_activation(activation),
+			bias_initializer: Initializer::Zeros,
+			dropout: 0.0,
+			softmax: false
 		}
 	}
 }
@@ -34,14 +59,14 @@ impl Layer {
 /// 
 /// Can be used by `Mentor` types to train it and become a trained neural network
 /// with which the user can predict data.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TopologyBuilder {
 	last  : usize,
 	layers: Vec<Layer>
 }
 
 /// Represents the neural network topology.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Topology {
 	layers: Vec<Layer>
 }
@@ -81,10 +106,46 @@ impl Topology {
 			.outputs
 	}
 
+	/// Returns the activation function of the output layer.
+	pub fn output_activation(&self) -> OutputActivation {
+		let layer = self.layers
+			.last()
+			.expect("a finished disciple must have a valid last layer!");
+		if layer.softmax { OutputActivation::SoftMax } else { layer.activation.into() }
+	}
+
+	/// Returns the number of layers excluding the input layer.
+	pub fn len_layers(&self) -> usize {
+		self.layers.len()
+	}
+
 	/// Iterates over the layer sizes of this topology.
 	pub fn iter_layers(&self) -> Iter<Layer> {
 		self.layers.iter()
 	}
+
+	/// Uses the given initializer for the weights of the output layer.
+	pub fn initializer(mut self, initializer: Initializer) -> Topology {
+		last_layer(&mut self.layers).initializer = initializer;
+		self
+	}
+
+	/// Uses the given initializer for the bias weights of the output layer.
+	pub fn bias_initializer(mut self, initializer: Initializer) -> Topology {
+		last_layer(&mut self.layers).bias_initializer = initializer;
+		self
+	}
+}
+
+/// Returns the most recently added layer.
+///
+/// # Panics
+///
+/// If there are no layers.
+fn last_layer(layers: &mut [Layer]) -> &mut Layer {
+	layers
+		.last_mut()
+		.expect("there must be a layer to configure!")
 }
 
 impl TopologyBuilder {
@@ -121,17 +182,59 @@ impl TopologyBuilder {
 		self
 	}
 
+	/// Uses the given initializer for the weights of the most recently added hidden layer.
+	///
+	/// # Panics
+	///
+	/// If no hidden layer has been added, yet.
+	pub fn initializer(mut self, initializer: Initializer) -> TopologyBuilder {
+		last_layer(&mut self.layers).initializer = initializer;
+		self
+	}
+
+	/// Uses the given initializer for the bias weights of the most recently added hidden layer.
+	///
+	/// # Panics
+	///
+	/// If no hidden layer has been added, yet.
+	pub fn bias_initializer(mut self, initializer: Initializer) -> TopologyBuilder {
+		last_layer(&mut self.layers).bias_initializer = initializer;
+		self
+	}
+
+	/// Randomly drops out outputs of the most recently added hidden layer
+	/// with the given probability while training.
+	///
+	/// Kept outputs are scaled up accordingly while training,
+	/// so predictions use all outputs without any scaling.
+	///
+	/// # Panics
+	///
+	/// If no hidden layer has been added, yet, or if `rate` is not within `[0,1)`.
+	pub fn dropout(mut self, rate: f32) -> TopologyBuilder {
+		assert!((0.0..1.0).contains(&rate), "dropout rate must be within [0,1)");
+		last_layer(&mut self.layers).dropout = rate;
+		self
+	}
+
 	/// Finishes constructing a topology by defining its output layer neurons.
 	///
+	/// The output layer takes any element-wise activation function
+	/// or `OutputActivation::SoftMax`.
+	///
 	/// Bias-Neurons are implicitely added!
 	/// 
 	/// # Panics
 	/// 
 	/// If `layer_size` is zero.
-	pub fn output(mut self, layer_size: usize, act: Activation) -> Topology {
+	pub fn output<A>(mut self, layer_size: usize, act: A) -> Topology
+		where A: Into<OutputActivation>
+	{
 		assert!(layer_size >= 1, "cannot define a zero-sized output layer");
 
-		self.push_layer(layer_size, act);
+		let act = act.into();
+		self.push_layer(layer_size, act.element_wise());
+		last_layer(&mut self.layers).softmax = act == OutputActivation::SoftMax;
 		Topology {
 			layers: self.layers,
 		}
@@ -153,10 +256,57 @@ mod tests {
 			])
 			.output(5, Tanh);
 		let mut it = dis.iter_layers()
-			.map(|&size| size);
+			.cloned();
 		assert_eq!(it.next(), Some(Layer::new(2, 5, Logistic)));
 		assert_eq!(it.next(), Some(Layer::new(5, 10, Identity)));
 		assert_eq!(it.next(), Some(Layer::new(10, 10, ReLU)));
 		assert_eq!(it.next(), Some(Layer::new(10, 5, Tanh)));
 	}
+
+	#[test]
+	fn initializers() {
+		use self::Activation::{ReLU, Tanh};
+		let top = Topology::input(2)
+			.layer(5, ReLU)
+			.layer(3, Tanh)
+			.initializer(Initializer::LeCunNormal)
+			.bias_initializer(Initializer::Uniform(-0.1, 0.1))
+			.output(1, Tanh)
+			.initializer(Initializer::XavierNormal);
+		let layers = top.iter_layers().collect::<Vec<_>>();
+		assert_eq!(layers[0].initializer, Initializer::HeUniform);
+		assert_eq!(layers[0].bias_initializer, Initializer::Zeros);
+		assert_eq!(layers[1].initializer, Initializer::LeCunNormal);
+		assert_eq!(layers[1].bias_initializer, Initializer::Uniform(-0.1, 0.1));
+		assert_eq!(layers[2].initializer, Initializer::XavierNormal);
+	}
+
+	#[test]
+	fn dropout() {
+		use self::Activation::Tanh;
+		let top = Topology::input(2)
+			.layer(5, Tanh)
+			.dropout(0.5)
+			.layer(3, Tanh)
+			.output(1, Tanh);
+		let rates = top.iter_layers().map(|layer| layer.dropout).collect::<Vec<_>>();
+		assert_eq!(rates, vec![0.5, 0.0, 0.0]);
+	}
+
+	#[test]
+	#[should_panic(expected = "dropout rate must be within [0,1)")]
+	fn invalid_dropout() {
+		Topology::input(2).layer(3, Activation::Tanh).dropout(1.0);
+	}
+
+	#[test]
+	fn softmax_output() {
+		let top = Topology::input(2).output(3, OutputActivation::SoftMax);
+		assert_eq!(top.output_activation(), OutputActivation::SoftMax);
+		let output = top.iter_layers().last().unwrap();
+		assert_eq!((output.activation, output.softmax), (Activation::Identity, true));
+		let top = Topology::input(2).output(3, Activation::Tanh);
+		assert_eq!(top.output_activation(), OutputActivation::Element(Activation::Tanh));
+		assert!(!top.iter_layers().last().unwrap().softmax);
+	}
 }
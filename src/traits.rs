@@ -4,9 +4,12 @@ use ndarray::*;
 
 use errors::Result;
 use errors::ErrorKind::{InvalidLearnRate, InvalidLearnMomentum};
+use optimizer::Optimizer;
+use loss::Loss;
 
 /// Learn rate.
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct LearnRate(pub f32);
 
 impl LearnRate {
@@ -31,6 +34,7 @@ impl Default for LearnRate {
 
 /// Learn momentum.
 #[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde_support", derive(Serialize, Deserialize))]
 pub struct LearnMomentum(pub f32);
 
 impl LearnMomentum {
@@ -60,13 +64,33 @@ pub trait Predict<I> {
 	fn predict(&mut self, input: I) -> ArrayView1<f32>;
 }
 
+/// Types that can predict data for a batch of inputs at once.
+/// Used by learning procedures.
+///
+/// This trait should only be used internally!
+pub trait PredictBatch<I> {
+	/// Predicts data based on given input data with one input per row.
+	fn predict_batch(&mut self, inputs: I) -> ArrayView2<f32>;
+}
+
 /// Types that can propagate through gradient descent.
 /// Used by learning procedures.
 ///
 /// This trait should only be used internally!
 pub trait UpdateGradients<T> {
-	/// Performs gradient descent within the neural network.
-	fn update_gradients(&mut self, target: T);
+	/// Performs gradient descent within the neural network
+	/// with the gradients of the given loss function.
+	fn update_gradients(&mut self, target: T, loss: Loss);
+}
+
+/// Types that can propagate through gradient descent for a batch of targets at once.
+/// Used by learning procedures.
+///
+/// This trait should only be used internally!
+pub trait UpdateGradientsBatch<T> {
+	/// Performs gradient descent within the neural network with one target per row
+	/// and the gradients of the given loss function.
+	fn update_gradients_batch(&mut self, targets: T, loss: Loss);
 }
 
 /// Types that can adjust their internal weights.
@@ -77,3 +101,26 @@ pub trait UpdateWeights<I> {
 	/// Updates weights based on the given input data and the current gradients.
 	fn update_weights(&mut self, input: I, rate: LearnRate, momentum: LearnMomentum);
 }
+
+/// Types that can adjust their internal weights based on a batch of inputs at once.
+/// Used by learning procedures.
+///
+/// This trait should only be used internally!
+pub trait UpdateWeightsBatch<I> {
+	/// Updates weights based on the given input data with one input per row
+	/// and the current gradients averaged over the batch.
+	fn update_weights_batch(&mut self, inputs: I, rate: LearnRate, momentum: LearnMomentum);
+}
+
+/// Types that can switch the optimizer used to adjust their internal weights.
+/// Used by learning procedures.
+///
+/// This trait should only be used internally!
+pub trait SetOptimizer {
+	/// Switches to the given optimizer for all following weight updates.
+	fn set_optimizer(&mut self, optimizer: Optimizer);
+
+	/// Switches to the given optimizer and discards all accumulated
+	/// optimizer state such as the momentum of previous weight updates.
+	fn reset_optimizer(&mut self, optimizer: Optimizer);
+}
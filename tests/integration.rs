@@ -3,6 +3,7 @@ extern crate prophet;
 
 extern crate rand;
 extern crate itertools;
+extern crate ndarray;
 
 #[macro_use]
 extern crate approx;
@@ -75,7 +76,7 @@ fn train_xor() {
 		[t, t] => f
 	];
 
-	let net = Topology::input(2)
+	let (net, _) = Topology::input(2)
 		.layer(4, Tanh)
 		.layer(3, Tanh)
 		.output(1, Tanh)
@@ -112,7 +113,7 @@ fn train_constant() {
 		0.9 => 1.0
 	];
 
-	let net = Topology::input(1)
+	let (net, _) = Topology::input(1)
 		.output(1, Identity)
 
 		.train(learn_samples)
@@ -135,7 +136,7 @@ fn train_and() {
 		[t, t] => t
 	];
 
-	let net = Topology::input(2)
+	let (net, _) = Topology::input(2)
 		.output(1, Tanh)
 
 		.train(samples.clone())
@@ -164,7 +165,7 @@ fn train_triple_add() {
 	let test_samples = gen_random_samples(
 		count_test_samples, inputs, outputs, mapper);
 
-	let net = Topology::input(inputs)
+	let (net, _) = Topology::input(inputs)
 		.output(outputs, Identity)
 
 		.train(learn_samples)
@@ -198,7 +199,7 @@ fn train_compare() {
 	let test_samples = gen_random_samples(
 		count_test_samples, inputs, outputs, mapper);
 
-	let net = Topology::input(inputs)
+	let (net, _) = Topology::input(inputs)
 		.layer(4, Tanh)
 		.layer(3, Tanh)
 		.output(outputs, Tanh)
@@ -210,3 +211,1024 @@ fn train_compare() {
 
 	validate_exact(net, test_samples)
 }
+
+#[test]
+fn train_xor_with_optimizers() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[t, f] => t,
+		[f, t] => t,
+		[t, t] => f
+	];
+
+	let optimizers = vec![
+		Optimizer::Nesterov,
+		Optimizer::ada_grad(),
+		Optimizer::rms_prop(),
+		Optimizer::adam()
+	];
+
+	for optimizer in optimizers {
+		let (net, _) = Topology::input(2)
+			.layer(4, Tanh)
+			.layer(3, Tanh)
+			.output(1, Tanh)
+
+			.train(samples.clone())
+			.learn_rate(0.05)
+			.optimizer(optimizer)
+			.log_config(LogConfig::TimeSteps(Duration::from_secs(1)))
+			.go()
+			.unwrap();
+
+		validate_rounded(net, samples.clone());
+	}
+}
+
+#[test]
+fn train_with_custom_optimizer() {
+	use ndarray::prelude::*;
+	use Activation::Identity;
+
+	/// Plain gradient descent without any momentum.
+	struct GradientDescent;
+
+	impl UpdateRule for GradientDescent {
+		type State = ();
+
+		fn new_state(&self, _: (usize, usize)) {}
+
+		fn update(&self, _: &mut (), mut weights: ArrayViewMut2<f32>, gradients: ArrayView2<f32>, rate: f32, _: f32) {
+			weights.scaled_add(rate, &gradients);
+		}
+	}
+
+	let samples = (0..10)
+		.map(|i| {
+			let x = i as f32 / 10.0;
+			Sample::new(vec![x], vec![0.5 * x + 0.25])
+		})
+		.collect::<Vec<_>>();
+
+	let (mut net, _) = Topology::input(1)
+		.output(1, Identity)
+
+		.train(samples.clone())
+		.learn_rate(0.1)
+		.optimizer(Optimizer::custom(GradientDescent))
+		.criterion(Criterion::Iterations(5000))
+		.go()
+		.unwrap();
+
+	for sample in samples {
+		assert_relative_eq!(net.predict(sample.input.view())[0], sample.target[0], epsilon = 0.01);
+	}
+}
+
+#[test]
+fn train_xor_with_batches() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[t, f] => t,
+		[f, t] => t,
+		[t, t] => f
+	];
+
+	for &batch_size in &[BatchSize::Mini(2), BatchSize::Full] {
+		let (net, _) = Topology::input(2)
+			.layer(4, Tanh)
+			.layer(3, Tanh)
+			.output(1, Tanh)
+
+			.train(samples.clone())
+			.learn_rate(0.6)
+			.batch_size(batch_size)
+			.log_config(LogConfig::TimeSteps(Duration::from_secs(1)))
+			.go()
+			.unwrap();
+
+		validate_rounded(net, samples.clone());
+	}
+}
+
+#[test]
+fn batch_larger_than_samples() {
+	let result = Topology::input(1)
+		.output(1, Activation::Identity)
+
+		.train(samples![[0.0] => 0.0, [1.0] => 1.0])
+		.batch_size(BatchSize::Mini(3))
+		.go();
+
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidBatchSize);
+}
+
+#[test]
+fn train_triple_add_with_mini_batches() {
+	use Activation::Identity;
+
+	let count_learn_samples = 10_000;
+	let count_test_samples  =    100;
+	let inputs  = 3;
+	let outputs = 1;
+
+	fn mapper(inputs: &[f32]) -> Vec<f32> {
+		vec![inputs[0] + inputs[1] + inputs[2]]
+	}
+
+	let learn_samples = gen_random_samples(
+		count_learn_samples, inputs, outputs, mapper);
+	let test_samples = gen_random_samples(
+		count_test_samples, inputs, outputs, mapper);
+
+	let (net, _) = Topology::input(inputs)
+		.output(outputs, Identity)
+
+		.train(learn_samples)
+		.batch_size(BatchSize::Mini(32))
+		.log_config(LogConfig::TimeSteps(Duration::from_secs(1)))
+		.go()
+		.unwrap();
+
+	validate_exact(net, test_samples)
+}
+
+#[test]
+fn train_noisy_triple_add_with_early_stopping() {
+	use Activation::Identity;
+
+	let inputs  = 3;
+	let outputs = 1;
+
+	fn mapper(inputs: &[f32]) -> Vec<f32> {
+		vec![inputs[0] + inputs[1] + inputs[2]]
+	}
+
+	fn noisy_mapper(inputs: &[f32]) -> Vec<f32> {
+		let noise = thread_rng().gen::<f32>() * 0.4 - 0.2;
+		vec![inputs[0] + inputs[1] + inputs[2] + noise]
+	}
+
+	let learn_samples      = gen_random_samples(1_000, inputs, outputs, noisy_mapper);
+	let validation_samples = gen_random_samples(  200, inputs, outputs, mapper);
+	let test_samples       = gen_random_samples(  100, inputs, outputs, mapper);
+
+	let (mut net, _) = Topology::input(inputs)
+		.output(outputs, Identity)
+
+		.train(learn_samples)
+		.validation(validation_samples)
+		.criterion(Criterion::EarlyStopping(5))
+		.log_config(LogConfig::TimeSteps(Duration::from_secs(1)))
+		.go()
+		.unwrap();
+
+	for sample in test_samples {
+		let predicted = net.predict(sample.input.view())[0];
+		assert!((predicted - sample.target[0]).abs() < 0.25);
+	}
+}
+
+#[test]
+fn early_stopping_requires_validation_samples() {
+	use Activation::Tanh;
+
+	let samples = samples![
+		[0.0, 0.0] => 0.0
+	];
+
+	let result = Topology::input(2)
+		.output(1, Tanh)
+
+		.train(samples)
+		.criterion(Criterion::EarlyStopping(10))
+		.go();
+
+	assert_eq!(result.unwrap_err(), ErrorKind::MissingValidationSamples);
+}
+
+#[test]
+fn report_training() {
+	use Activation::Identity;
+
+	// impossible to learn for a single linear neuron
+	let samples = samples![
+		[0.0, 0.0] => 1.0,
+		[1.0, 0.0] => 0.0,
+		[0.0, 1.0] => 0.0,
+		[1.0, 1.0] => 1.0
+	];
+
+	let (_, report) = Topology::input(2)
+		.output(1, Identity)
+
+		.train(samples)
+		.criterion(Criterion::LatestLoss(1e-12).or(Criterion::Iterations(1000)))
+		.history_interval(100)
+		.go()
+		.unwrap();
+
+	assert_eq!(report.stop_reason, StopReason::Criterion(Criterion::Iterations(1000)));
+	assert_eq!(report.stats.iterations, 1000);
+	assert_eq!(report.stats.learn_rate_policy, LearnRatePolicy::BoldDriver{increase: 1.1, decrease: 0.5});
+	assert_eq!(report.history.len(), 10);
+	assert_eq!(report.history[0].iterations, 100);
+}
+
+#[test]
+fn stop_by_observer() {
+	use Activation::Identity;
+
+	// impossible to learn for a single linear neuron
+	let samples = samples![
+		[0.0, 0.0] => 1.0,
+		[1.0, 0.0] => 0.0,
+		[0.0, 1.0] => 0.0,
+		[1.0, 1.0] => 1.0
+	];
+
+	let (_, report) = Topology::input(2)
+		.output(1, Identity)
+
+		.train(samples)
+		.criterion(Criterion::LatestLoss(1e-12))
+		.observer(MetricsWriter::csv(std::io::sink(), 100))
+		.observer(|stats: &Stats, _: &NeuralNet| {
+			if stats.iterations >= 500 { Observation::Stop } else { Observation::Continue }
+		})
+		.go()
+		.unwrap();
+
+	assert_eq!(report.stop_reason, StopReason::Observer);
+	assert_eq!(report.stats.iterations, 500);
+}
+
+#[test]
+fn resume_training() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[f, t] => f,
+		[t, f] => f,
+		[t, t] => t
+	];
+
+	let (net, _) = Topology::input(2)
+		.output(1, Tanh)
+
+		.train(samples.clone())
+		.optimizer(Optimizer::adam())
+		.learn_rate(0.05)
+		.go()
+		.unwrap();
+
+	let (mut net, report) = net
+		.train(samples.clone())
+		.learn_rate(0.001)
+		.keep_optimizer_state(true)
+		.criterion(Criterion::Iterations(10))
+		.go()
+		.unwrap();
+
+	assert_eq!(net.optimizer(), Optimizer::adam());
+	assert_eq!(report.stats.iterations, 10);
+	assert_eq!(report.stats.learn_rate_policy, LearnRatePolicy::Fixed);
+	for sample in samples {
+		let predicted = net.predict(sample.input.view())[0];
+		assert_eq!(predicted.round(), sample.target[0]);
+	}
+}
+
+#[test]
+#[cfg(feature = "serde_support")]
+fn resume_training_from_checkpoint() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[f, t] => t,
+		[t, f] => t,
+		[t, t] => f
+	];
+
+	let path = std::env::temp_dir()
+		.join(format!("prophet-integration-checkpoint-{}", std::process::id()));
+
+	let (_, report) = Topology::input(2)
+		.layer(4, Tanh)
+		.output(1, Tanh)
+
+		.train(samples.clone())
+		.criterion(Criterion::Iterations(500))
+		.checkpoint(path.clone(), 100)
+		.go()
+		.unwrap();
+
+	assert_eq!(report.stats.iterations, 500);
+	let checkpoint = Checkpoint::load(&path).unwrap();
+	assert_eq!(checkpoint.iterations(), 500);
+
+	let (_, report) = checkpoint
+		.train(samples)
+		.criterion(Criterion::Iterations(700))
+		.go()
+		.unwrap();
+
+	assert_eq!(report.stats.iterations, 700);
+	std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn seeded_training_is_reproducible() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[f, t] => t,
+		[t, f] => t,
+		[t, t] => f
+	];
+
+	let train = |seed| {
+		Topology::input(2)
+			.layer(3, Tanh)
+			.output(1, Tanh)
+
+			.train(samples.clone())
+			.scheduling(Scheduling::Random)
+			.criterion(Criterion::Iterations(1000))
+			.seed(seed)
+			.go()
+			.unwrap()
+			.0
+	};
+
+	let (mut fst, mut snd, mut other) = (train(42), train(42), train(7));
+	for sample in &samples {
+		let expected = fst.predict(sample.input.view()).to_owned();
+		assert_eq!(snd.predict(sample.input.view()), expected);
+		assert!(other.predict(sample.input.view()) != expected);
+	}
+}
+
+#[test]
+#[cfg(feature = "serde_support")]
+fn resumed_seeded_training_is_reproducible() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[f, t] => t,
+		[t, f] => t,
+		[t, t] => f
+	];
+
+	for &scheduling in &[Scheduling::Random, Scheduling::Shuffled] {
+		let path = std::env::temp_dir()
+			.join(format!("prophet-integration-resumed-{}-{:?}", std::process::id(), scheduling));
+
+		let train = |iterations| {
+			Topology::input(2)
+				.layer(6, Tanh)
+				.dropout(0.25)
+				.output(1, Tanh)
+
+				.train(samples.clone())
+				.scheduling(scheduling)
+				.criterion(Criterion::Iterations(iterations))
+				.checkpoint(path.clone(), 200)
+				.seed(42)
+				.go()
+				.unwrap()
+				.0
+		};
+
+		let mut uninterrupted = train(400);
+		let _ = train(200);
+		let (mut resumed, _) = Checkpoint::load(&path)
+			.unwrap()
+			.train(samples.clone())
+			.scheduling(scheduling)
+			.criterion(Criterion::Iterations(400))
+			.go()
+			.unwrap();
+		std::fs::remove_file(&path).unwrap();
+
+		for sample in &samples {
+			assert_eq!(
+				resumed.predict(sample.input.view()),
+				uninterrupted.predict(sample.input.view()),
+				"scheduling: {:?}", scheduling
+			);
+		}
+	}
+}
+
+#[test]
+fn train_xor_with_shuffled_epochs() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[f, t] => t,
+		[t, f] => t,
+		[t, t] => f
+	];
+
+	let (net, report) = Topology::input(2)
+		.layer(4, Tanh)
+		.output(1, Tanh)
+
+		.train(samples.clone())
+		.scheduling(Scheduling::Shuffled)
+		.criterion(Criterion::RecentLoss(0.05).and(Criterion::Epochs(100)))
+		.seed(42)
+		.go()
+		.unwrap();
+
+	assert!(report.stats.epochs >= 100);
+	assert_eq!(report.stats.epochs, report.stats.iterations / 4);
+	validate_rounded(net, samples);
+}
+
+#[test]
+fn weighted_scheduling_requires_valid_weights() {
+	use Activation::Tanh;
+
+	let samples = vec![
+		Sample::new(vec![0.0], vec![0.0]).with_weight(0.0),
+		Sample::new(vec![1.0], vec![1.0]).with_weight(0.0)
+	];
+
+	let result = Topology::input(1)
+		.output(1, Tanh)
+
+		.train(samples.clone())
+		.scheduling(Scheduling::Weighted)
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidSampleWeight);
+
+	let result = Topology::input(1)
+		.output(1, Tanh)
+
+		.train(vec![samples[0].clone().with_weight(-1.0)])
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidSampleWeight);
+}
+
+#[test]
+fn train_xor_from_file_source() {
+	use Activation::Tanh;
+
+	fn parse(line: &str) -> Option<Sample> {
+		let values = line
+			.split(',')
+			.map(|value| value.trim().parse::<f32>().ok())
+			.collect::<Option<Vec<_>>>()?;
+		Some(Sample::new(&values[..2], &values[2..]))
+	}
+
+	let path = std::env::temp_dir()
+		.join(format!("prophet-integration-source-{}", std::process::id()));
+	std::fs::write(&path, "-1,-1,-1\n-1,1,1\n1,-1,1\n1,1,-1\n").unwrap();
+
+	let (net, report) = Topology::input(2)
+		.layer(4, Tanh)
+		.output(1, Tanh)
+
+		.train_from_source(FileSource::open(&path, parse).unwrap())
+		.batch_size(BatchSize::Mini(2))
+		.learn_rate(0.25)
+		.learn_momentum(0.6)
+		.criterion(Criterion::RecentLoss(0.05).and(Criterion::Epochs(100)))
+		.go()
+		.unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	assert_eq!(report.stats.epochs, report.stats.iterations / 4);
+	let (t, f) = (1.0, -1.0);
+	validate_rounded(net, samples![
+		[f, f] => f,
+		[f, t] => t,
+		[t, f] => t,
+		[t, t] => f
+	]);
+}
+
+#[test]
+fn train_xor_from_csv_file() {
+	use Activation::Tanh;
+
+	let path = std::env::temp_dir()
+		.join(format!("prophet-integration-csv-{}", std::process::id()));
+	std::fs::write(&path, "\"a\";\"b\";\"a xor b\"\n-1;-1;-1\n-1;1;1\n\n1;-1;1\n1;1;-1\n").unwrap();
+	let samples = CsvLoader::new()
+		.delimiter(';')
+		.header(true)
+		.targets(Columns::Names(vec!["a xor b".to_owned()]))
+		.load(&path)
+		.unwrap();
+	std::fs::remove_file(&path).unwrap();
+	assert_eq!(samples.len(), 4);
+
+	let (net, _) = Topology::input(2)
+		.layer(4, Tanh)
+		.output(1, Tanh)
+
+		.train(samples.clone())
+		.learn_rate(0.25)
+		.learn_momentum(0.6)
+		.criterion(Criterion::RecentLoss(0.05))
+		.seed(42)
+		.go()
+		.unwrap();
+
+	validate_rounded(net, samples);
+}
+
+#[test]
+fn sample_sources_require_iterative_scheduling() {
+	use Activation::Tanh;
+
+	let samples = samples![
+		[0.0] => 0.0,
+		[1.0] => 1.0
+	];
+
+	let result = Topology::input(1)
+		.output(1, Tanh)
+
+		.train_from_source(MemorySource::new(samples))
+		.scheduling(Scheduling::Shuffled)
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidScheduling);
+
+	let result = Topology::input(1)
+		.output(1, Tanh)
+
+		.train_from_source(MemorySource::new(vec![]))
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::EmptySampleSource);
+}
+
+/// Trains on the MNIST database of handwritten digits.
+///
+/// Requires the uncompressed MNIST files in the directory given
+/// by the `MNIST_DIR` environment variable and is ignored by default.
+#[test]
+#[ignore]
+fn train_mnist() {
+	use Activation::{ReLU, Logistic};
+
+	let dir = std::path::PathBuf::from(std::env::var("MNIST_DIR")
+		.expect("MNIST_DIR must point to the uncompressed MNIST files"));
+	let train = load_idx(
+		dir.join("train-images-idx3-ubyte"), dir.join("train-labels-idx1-ubyte"), 10).unwrap();
+	let test = load_idx(
+		dir.join("t10k-images-idx3-ubyte"), dir.join("t10k-labels-idx1-ubyte"), 10).unwrap();
+
+	let (mut net, _) = Topology::input(784)
+		.layer(100, ReLU)
+		.output(10, Logistic)
+
+		.train(train)
+		.scheduling(Scheduling::Shuffled)
+		.batch_size(BatchSize::Mini(32))
+		.criterion(Criterion::Epochs(3))
+		.seed(42)
+		.go()
+		.unwrap();
+
+	let argmax = |values: &[f32]| values
+		.iter()
+		.enumerate()
+		.fold(0, |best, (index, &value)| if value > values[best] { index } else { best });
+	let correct = test
+		.iter()
+		.filter(|sample| {
+			let predicted = net.predict(sample.input.view()).to_vec();
+			argmax(&predicted) == argmax(sample.target.as_slice().unwrap())
+		})
+		.count();
+	assert!(correct as f64 / test.len() as f64 > 0.9);
+}
+
+#[test]
+fn train_scaled_regression() {
+	use Activation::{Tanh, Identity};
+
+	let samples = (0..21)
+		.map(|i| {
+			let x = i as f32 * 50.0;
+			Sample::new(vec![x], vec![3.0 * x + 1000.0])
+		})
+		.collect::<Vec<_>>();
+
+	let (mut net, _) = Topology::input(1)
+		.layer(4, Tanh)
+		.output(1, Identity)
+
+		.train(samples.clone())
+		.learn_rate(0.1)
+		.learn_momentum(0.5)
+		.criterion(Criterion::RecentLoss(1e-4).or(Criterion::Iterations(100_000)))
+		.seed(42)
+		.scaling(Scaler::MinMax, Scaler::ZScore)
+		.go()
+		.unwrap();
+
+	assert_eq!(net.input_scaling().unwrap().offsets()[0], 0.0);
+	assert_eq!(net.output_scaling().unwrap().offsets()[0], 2500.0);
+	for sample in &samples {
+		let predicted = net.predict(sample.input.view())[0];
+		assert!((predicted - sample.target[0]).abs() < 50.0, "{} vs {}", predicted, sample.target[0]);
+	}
+}
+
+#[test]
+fn per_column_scalers_require_all_columns() {
+	use Activation::Tanh;
+
+	let result = Topology::input(2)
+		.output(1, Tanh)
+		.train(samples![[0.0, 1.0] => 0.0])
+		.scaling(Scaler::PerColumn(vec![Scaler::MinMax]), Scaler::Identity)
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidScaler);
+}
+
+#[test]
+fn train_or_with_binary_cross_entropy() {
+	use Activation::{Tanh, Logistic};
+
+	let (t, f) = (1.0, 0.0);
+	let samples = samples![
+		[f, f] => f,
+		[f, t] => t,
+		[t, f] => t,
+		[t, t] => t
+	];
+
+	let (net, report) = Topology::input(2)
+		.layer(3, Tanh)
+		.output(1, Logistic)
+
+		.train(samples.clone())
+		.loss(Loss::BinaryCrossEntropy)
+		.criterion(Criterion::RecentLoss(0.05))
+		.go()
+		.unwrap();
+
+	assert!(report.stats.recent_loss <= 0.05);
+	validate_rounded(net, samples)
+}
+
+#[test]
+fn softmax_requires_categorical_cross_entropy() {
+	use OutputActivation::SoftMax;
+
+	let result = Topology::input(2)
+		.output(2, SoftMax)
+		.train(samples![[0.0, 1.0] => [0.0, 1.0]])
+		.loss(Loss::MeanSquaredError)
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidLoss);
+}
+
+#[test]
+fn regularization_shrinks_weights() {
+	use Activation::Identity;
+
+	let samples = samples![
+		[-1.0] => -2.0,
+		[ 0.0] =>  0.0,
+		[ 1.0] =>  2.0
+	];
+
+	let train = |regularization| {
+		let (mut net, report) = Topology::input(1)
+			.output(1, Identity)
+
+			.train(samples.clone())
+			.learn_rate(0.1)
+			.regularization(regularization)
+			.criterion(Criterion::Iterations(3000))
+			.seed(42)
+			.go()
+			.unwrap();
+		(net.predict(&[1.0][..])[0], report.stats.latest_loss)
+	};
+
+	let (plain, _)    = train(Regularization::default());
+	let (l2, l2_loss) = train(Regularization::l2(1.0));
+	let (decayed, _)  = train(Regularization::weight_decay(1.0));
+	assert_relative_eq!(plain, 2.0, epsilon = 1e-3);
+	assert!(l2 < 1.5, "prediction with L2 regularization: {}", l2);
+	assert!(decayed < 1.5, "prediction with weight decay: {}", decayed);
+	assert!(l2_loss > 0.1, "loss must include the L2 penalty: {}", l2_loss);
+}
+
+#[test]
+fn weight_decay_must_not_flip_weights() {
+	let train = |learn_rate: f64| Topology::input(1)
+		.output(1, Activation::Identity)
+		.train(samples![[1.0] => 2.0])
+		.learn_rate(learn_rate)
+		.regularization(Regularization::weight_decay(3.0))
+		.criterion(Criterion::Iterations(10))
+		.go();
+
+	assert_eq!(train(0.5).unwrap_err(), ErrorKind::InvalidRegularization);
+	assert!(train(0.1).is_ok());
+}
+
+#[test]
+fn layer_regularization_requires_every_layer() {
+	use Activation::Tanh;
+
+	let result = Topology::input(2)
+		.layer(2, Tanh)
+		.output(1, Tanh)
+		.train(samples![[0.0, 1.0] => 0.0])
+		.layer_regularization(vec![Regularization::l1(0.1); 3])
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidRegularization);
+}
+
+#[test]
+fn gradient_clipping_prevents_divergence() {
+	use Activation::{ReLU, Identity};
+
+	let samples = samples![
+		[ 5.0] => 10.0,
+		[10.0] => 20.0,
+		[20.0] => 40.0
+	];
+
+	let train = |clipping| {
+		Topology::input(1)
+			.layer(4, ReLU)
+			.output(1, Identity)
+
+			.train(samples.clone())
+			.learn_rate(0.5)
+			.learn_momentum(0.5)
+			.gradient_clipping(clipping)
+			.criterion(Criterion::Iterations(2000))
+			.seed(42)
+			.go()
+			.map(|(mut net, report)| (net.predict(&[10.0][..])[0], report.stats.latest_loss))
+	};
+
+	let plain               = train(GradientClipping::None);
+	let (value, value_loss) = train(GradientClipping::Value(1.0)).unwrap();
+	let (norm, norm_loss)   = train(GradientClipping::Norm(1.0)).unwrap();
+	assert!(matches!(plain, Err(ErrorKind::TrainingDiverged{..})), "training without clipping: {:?}", plain);
+	assert!(value.is_finite() && value_loss.is_finite(), "prediction with value clipping: {}", value);
+	assert!(norm.is_finite() && norm_loss.is_finite(), "prediction with norm clipping: {}", norm);
+}
+
+#[test]
+fn gradient_clipping_requires_positive_threshold() {
+	use Activation::Tanh;
+
+	let result = Topology::input(2)
+		.output(1, Tanh)
+		.train(samples![[0.0, 1.0] => 0.0])
+		.gradient_clipping(GradientClipping::Norm(0.0))
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidGradientClipping);
+}
+
+#[test]
+fn diverged_training_fails_or_rolls_back() {
+	use Activation::{ReLU, Identity};
+
+	let samples = samples![
+		[ 5.0] => 10.0,
+		[10.0] => 20.0,
+		[20.0] => 40.0
+	];
+
+	let mentor = || {
+		Topology::input(1)
+			.layer(4, ReLU)
+			.output(1, Identity)
+
+			.train(samples.clone())
+			.learn_rate(0.3)
+			.learn_momentum(0.5)
+			.criterion(Criterion::Iterations(2000))
+			.seed(42)
+	};
+
+	match mentor().go() {
+		Err(ErrorKind::TrainingDiverged{iteration, layer}) => {
+			assert!(iteration > 0 && iteration <= 2000, "diverged at iteration {}", iteration);
+			assert!(layer < 2, "diverged at layer {}", layer);
+		}
+		other => panic!("training must diverge: {:?}", other.map(|(_, report)| report.stats))
+	}
+
+	let (mut net, report) = mentor()
+		.rollback_interval(100)
+		.go()
+		.unwrap();
+	assert_eq!(report.stats.iterations, 2000);
+	assert!(report.stats.learn_rate < 0.5, "learn rate after rollbacks: {}", report.stats.learn_rate);
+	assert!(report.stats.latest_loss.is_finite());
+	assert!(net.predict(&[10.0][..])[0].is_finite());
+}
+
+#[test]
+fn rollback_requires_positive_interval() {
+	use Activation::Tanh;
+
+	let result = Topology::input(2)
+		.output(1, Tanh)
+		.train(samples![[0.0, 1.0] => 0.0])
+		.rollback_interval(0)
+		.go();
+	assert_eq!(result.unwrap_err(), ErrorKind::InvalidRollbackInterval);
+}
+
+#[test]
+fn train_and_with_dropout() {
+	use Activation::Tanh;
+
+	let (t, f) = (1.0, -1.0);
+	let samples = samples![
+		[f, f] => f,
+		[f, t] => f,
+		[t, f] => f,
+		[t, t] => t
+	];
+
+	let train = || {
+		Topology::input(2)
+			.layer(8, Tanh)
+			.dropout(0.25)
+			.output(1, Tanh)
+
+			.train(samples.clone())
+			.criterion(Criterion::Iterations(2000))
+			.seed(7)
+			.go()
+			.unwrap()
+	};
+
+	let (mut net, _) = train();
+	let (mut same, _) = train();
+	let first = net.predict(&[t, t][..]).to_owned();
+	assert_eq!(net.predict(&[t, t][..]), first);
+	assert_eq!(same.predict(&[t, t][..]), first);
+	validate_rounded(net, samples)
+}
+
+#[test]
+fn cross_validate_linear_function() {
+	use Activation::{Tanh, Identity};
+
+	let samples = (0..20)
+		.map(|i| {
+			let x = i as f32 / 10.0 - 1.0;
+			Sample::new(vec![x], vec![0.5 * x])
+		})
+		.collect::<Vec<_>>();
+
+	let split = Split::new(samples, 0.0, 0.2, 42).unwrap();
+	assert_eq!((split.train.len(), split.test.len()), (16, 4));
+
+	let cv = Topology::input(1)
+		.layer(3, Tanh)
+		.output(1, Identity)
+
+		.train(split.train)
+		.learn_rate(0.1)
+		.learn_momentum(0.5)
+		.criterion(Criterion::Iterations(2000))
+		.seed(42)
+		.cross_validate(4, 7)
+		.unwrap();
+
+	assert_eq!(cv.folds.len(), 4);
+	assert!(cv.folds.iter().all(|fold| fold.report.stats.iterations == 2000));
+	let mean = cv.folds.iter().map(|fold| fold.test_loss).sum::<f64>() / 4.0;
+	assert_relative_eq!(cv.mean_loss, mean);
+	assert!(cv.mean_loss < 0.05, "mean test loss: {}", cv.mean_loss);
+}
+
+#[test]
+fn cross_validate_observes_every_fold_separately() {
+	use std::sync::{Arc, Mutex};
+	use Activation::Identity;
+
+	let samples = (0..12)
+		.map(|i| Sample::new(vec![i as f32 / 12.0], vec![i as f32 / 24.0]))
+		.collect::<Vec<_>>();
+
+	let observed = Arc::new(Mutex::new(vec![0; 3]));
+	let cv = Topology::input(1)
+		.output(1, Identity)
+
+		.train(samples)
+		.criterion(Criterion::Iterations(100))
+		.seed(42)
+		.cross_validate_observed(3, 7, |fold| {
+			let observed = Arc::clone(&observed);
+			move |stats: &Stats, _: &NeuralNet| {
+				observed.lock().unwrap()[fold] = stats.iterations;
+				Observation::Continue
+			}
+		})
+		.unwrap();
+
+	assert_eq!(cv.folds.len(), 3);
+	assert_eq!(*observed.lock().unwrap(), vec![100, 100, 100]);
+}
+
+#[test]
+#[cfg(feature = "serde_support")]
+fn cross_validate_checkpoints_every_fold_separately() {
+	use Activation::Identity;
+
+	let samples = (0..12)
+		.map(|i| Sample::new(vec![i as f32 / 12.0], vec![i as f32 / 24.0]))
+		.collect::<Vec<_>>();
+
+	let path = std::env::temp_dir()
+		.join(format!("prophet-integration-cv-checkpoint-{}", std::process::id()));
+
+	Topology::input(1)
+		.output(1, Identity)
+
+		.train(samples)
+		.criterion(Criterion::Iterations(100))
+		.checkpoint(path.clone(), 50)
+		.cross_validate(3, 7)
+		.unwrap();
+
+	assert!(!path.exists());
+	for fold in 0..3 {
+		let fold_path = path.with_file_name(format!("prophet-integration-cv-checkpoint-{}.fold-{}", std::process::id(), fold));
+		assert_eq!(Checkpoint::load(&fold_path).unwrap().iterations(), 100);
+		std::fs::remove_file(&fold_path).unwrap();
+	}
+}
+
+#[test]
+fn mentor_is_send() {
+	fn assert_send<T: Send>(_: &T) {}
+	let mentor = Topology::input(1)
+		.output(1, Activation::Identity)
+		.initializer(Initializer::custom(|_, _| 0.5))
+		.train(samples![[0.0] => 0.0])
+		.observer(MetricsWriter::csv(std::io::sink(), 100));
+	assert_send(&mentor);
+}
+
+#[test]
+fn train_softmax_classifier() {
+	use Activation::Tanh;
+	use OutputActivation::SoftMax;
+
+	let samples = samples![
+		[0.0, 0.0] => [1.0, 0.0, 0.0],
+		[0.1, 0.1] => [1.0, 0.0, 0.0],
+		[1.0, 0.0] => [0.0, 1.0, 0.0],
+		[0.9, 0.1] => [0.0, 1.0, 0.0],
+		[0.0, 1.0] => [0.0, 0.0, 1.0],
+		[0.1, 0.9] => [0.0, 0.0, 1.0]
+	];
+
+	let (mut net, _) = Topology::input(2)
+		.layer(6, Tanh)
+		.output(3, SoftMax)
+
+		.train(samples.clone())
+		.learn_rate(0.1)
+		.learn_momentum(0.5)
+		.criterion(Criterion::RecentLoss(0.01))
+		.seed(42)
+		.go()
+		.unwrap();
+
+	for sample in &samples {
+		let predicted = net.predict(sample.input.view()).to_owned();
+		assert_relative_eq!(predicted.scalar_sum(), 1.0, epsilon = 1e-5);
+		let class = |values: &[f32]| (0..3).max_by(|&a, &b| values[a].partial_cmp(&values[b]).unwrap());
+		assert_eq!(class(predicted.as_slice().unwrap()), class(sample.target.as_slice().unwrap()));
+	}
+}